@@ -3,6 +3,7 @@
 #![feature(rustc_private)]
 #![allow(unused)]
 
+extern crate rustc_ast;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_hir;
@@ -15,6 +16,8 @@ extern crate rustc_span;
 mod migrate;
 mod nodes;
 mod run_rustc;
+// The previous implementation, which isn't used anymore and isn't kept lint-free
+#[allow(clippy::all)]
 mod old {
     mod parse;
     mod replace;
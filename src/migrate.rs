@@ -1,36 +1,766 @@
 use crate::nodes::*;
 
-pub fn migrate<'hir>(expr: Expr<'hir>) -> Option<String> {
-    let closure = expr.closure()?;
-    let param = closure.single_param()?;
-
-    // Check that `b` in `|b| ...` is a serenity builder
-    let mut param_type = closure.single_param()?.type_().ref_()?;
-    let [crate_, module, .., builder_type] = &*param_type.adt()?.path().parts() else { return None };
-    if !(crate_ == "serenity" && module == "builder") {
-        return None;
+mod activity;
+mod attachment;
+mod cache_and_http;
+mod cache_getters;
+mod call_site;
+mod channels;
+mod collectors;
+mod components;
+mod edit;
+mod event_handler;
+mod framework;
+mod ids;
+mod interaction_response;
+mod permissions;
+mod renamed_paths;
+mod shard_manager;
+mod utils;
+mod webhooks;
+
+pub use activity::{migrate_activity, ActivityMigration};
+pub use attachment::{migrate_attachment, AttachmentMigration};
+pub use cache_and_http::{migrate_cache_and_http_field, migrate_cache_and_http_type};
+pub use cache_getters::migrate_cache_getter;
+pub use call_site::migrate_call_site;
+pub use channels::{migrate_category_arms, migrate_guild_channel_pat};
+pub use collectors::migrate_collector;
+pub use edit::migrate_edit_call;
+pub use event_handler::{migrate_event_handler_fn, EventHandlerMigration};
+pub use framework::migrate_framework_configure;
+pub use ids::migrate_id;
+pub use permissions::{migrate_permission, migrate_required_permissions};
+pub use renamed_paths::{
+    migrate_expr_path, migrate_pat_path, migrate_type_path, migrate_use_path, UseMigration,
+};
+pub use shard_manager::{migrate_shard_manager_lock, migrate_shard_manager_type};
+pub use utils::{migrate_utils_fn, migrate_utils_use};
+pub use webhooks::{migrate_create_webhook, migrate_fake_embed, CreateWebhookMigration};
+
+/// Explains why a builder closure can't be migrated automatically
+pub struct Unsupported {
+    pub span: rustc_span::Span,
+    pub message: String,
+}
+impl Unsupported {
+    fn new(span: rustc_span::Span, message: impl Into<String>) -> Self {
+        Self { span, message: message.into() }
+    }
+}
+
+/// Whether a function returning the given type can propagate a `serenity::Error` with `?`
+fn propagates_serenity_error(return_type: Type<'_>) -> bool {
+    let Some(adt) = return_type.adt() else { return false };
+    if adt.path().parts() != ["core", "result", "Result"] {
+        return false;
+    }
+    let Some(error) = return_type.generic_args().pop() else { return false };
+    let Some(adt) = error.adt() else { return false };
+    match &*adt.path().parts() {
+        [crate_, .., error] if crate_ == "serenity" && error == "Error" => true,
+        // Like `Box<dyn std::error::Error + Send + Sync>`
+        [crate_, boxed, box_] if crate_ == "alloc" && boxed == "boxed" && box_ == "Box" => {
+            let boxed = error.generic_args().first().and_then(|boxed| boxed.dyn_trait());
+            let boxed = boxed.map(|boxed| boxed.parts()).unwrap_or_default();
+            matches!(&*boxed, [crate_, module, error] if (crate_ == "core" || crate_ == "std") && module == "error" && error == "Error")
+        }
+        [crate_, error] => crate_ == "anyhow" && error == "Error",
+        _ => false,
+    }
+}
+
+/// A closure like `|b| ...`, where `b` is a serenity builder
+struct BuilderClosure<'hir> {
+    expr: Expr<'hir>,
+    param: Param<'hir>,
+    body: Expr<'hir>,
+    /// Like `CreateEmbed`
+    builder_type: String,
+}
+impl<'hir> BuilderClosure<'hir> {
+    fn new(expr: Expr<'hir>) -> Option<Self> {
+        let closure = expr.closure()?;
+        let param = closure.single_param()?;
+
+        // Check that `b` in `|b| ...` is a serenity builder, or the standard framework's
+        // `Configuration`, which is built like one
+        let parts = param.type_().ref_()?.adt()?.path().parts();
+        let is_builder =
+            matches!(&*parts, [crate_, module, ..] if crate_ == "serenity" && module == "builder");
+        let is_configuration = matches!(&*parts, [crate_, framework, standard, .., name] if crate_ == "serenity" && framework == "framework" && standard == "standard" && name == "Configuration");
+        if !(is_builder || is_configuration) {
+            return None;
+        }
+        let builder_type = parts.last()?.clone();
+
+        Some(Self { expr, param, body: closure.body(), builder_type })
+    }
+}
+
+/// A serenity 0.12 builder constructor, like `CreateButton::new_link`
+struct Constructor {
+    path: String,
+    /// Fields whose setter arguments are passed to the constructor instead, in order
+    required_fields: &'static [&'static str],
+    /// Fields whose setters don't exist anymore because the constructor takes care of them
+    obsolete_fields: &'static [&'static str],
+}
+impl Constructor {
+    /// The `::new()` constructor of the given builder, which may have been renamed in 0.12
+    fn new(builder_type: &str) -> Self {
+        let builder_type = renamed_builder(builder_type).unwrap_or(builder_type);
+        Self {
+            path: format!("{builder_type}::new"),
+            required_fields: required_fields(builder_type),
+            obsolete_fields: &[],
+        }
+    }
+}
+
+/// Turns a closure-style serenity builder like `|e| e.title("x")` into a value-style builder
+/// expression like `CreateEmbed::new().title("x")`. Arguments which are builder closures
+/// themselves are migrated recursively.
+///
+/// Returns None if `expr` is not a builder closure.
+pub fn migrate(expr: Expr<'_>) -> Option<Result<String, Unsupported>> {
+    let closure = BuilderClosure::new(expr)?;
+    Some(match &*closure.builder_type {
+        "CreateInteractionResponse" => interaction_response::migrate(&closure),
+        "CreateComponents" => migrate_vec_builder(
+            &closure,
+            "create_action_row",
+            "add_action_row",
+            "set_action_rows",
+            Some("set_action_row"),
+        ),
+        "CreateActionRow" => components::migrate_action_row(&closure),
+        "CreateButton" => components::migrate_button(&closure),
+        "CreateSelectMenu" => components::migrate_select_menu(&closure),
+        "CreateSelectMenuOptions" => {
+            migrate_vec_builder(&closure, "create_option", "add_option", "set_options", None)
+        }
+        "CreateApplicationCommands" => migrate_vec_builder(
+            &closure,
+            "create_application_command",
+            "add_application_command",
+            "set_application_commands",
+            None,
+        ),
+        _ => migrate_closure(&closure, &Constructor::new(&closure.builder_type)),
+    })
+}
+
+/// A migrated builder closure
+pub struct BuilderMigration {
+    pub replacement: String,
+    /// Why the error of reading an attachment in the builder isn't propagated with `?`, if it isn't
+    pub fallback_reason: Option<&'static str>,
+}
+
+/// Like [`migrate`], but also finds out whether an attachment in the builder falls back to
+/// `.expect(..)`, like in `|m| m.add_file(AttachmentType::Path(p))` in a function which doesn't
+/// return a `Result`
+///
+/// Returns None if `expr` is not a builder closure.
+pub fn migrate_builder(expr: Expr<'_>) -> Option<Result<BuilderMigration, Unsupported>> {
+    fn fallback_reason(expr: Expr<'_>) -> Option<&'static str> {
+        expr.subexprs().into_iter().find_map(|subexpr| match subexpr.closure() {
+            // Closure bodies aren't part of the subexpressions
+            Some(closure) => fallback_reason(closure.body()),
+            None => migrate_attachment(subexpr)?.ok()?.fallback_reason,
+        })
+    }
+
+    let replacement = match migrate(expr)? {
+        Ok(replacement) => replacement,
+        Err(unsupported) => return Some(Err(unsupported)),
+    };
+    Some(Ok(BuilderMigration { replacement, fallback_reason: fallback_reason(expr) }))
+}
+
+/// Builders which were renamed in serenity 0.12
+fn renamed_builder(builder_type: &str) -> Option<&'static str> {
+    Some(match builder_type {
+        "CreateApplicationCommand" => "CreateCommand",
+        "CreateApplicationCommandOption" => "CreateCommandOption",
+        _ => return None,
+    })
+}
+
+/// Setters which took a builder closure in serenity 0.11 and were renamed when switching to
+/// builder values in 0.12
+fn renamed_setter(method_name: &str) -> Option<&'static str> {
+    Some(match method_name {
+        "create_option" => "add_option",
+        "create_sub_option" => "add_sub_option",
+        _ => return None,
+    })
+}
+
+/// The single argument of a method call like `.create_button(|b| ..)`
+fn single_arg<'hir>(call: &MethodCall<'hir>) -> Result<Expr<'hir>, Unsupported> {
+    let mut args = call.args();
+    args.next()
+        .filter(|_| args.next().is_none())
+        .ok_or_else(|| Unsupported::new(call.span(), "expected a single argument"))
+}
+
+/// Migrates the argument of a method call like `.create_button(|b| ..)`, which must be a builder
+/// closure
+fn migrate_nested_closure(call: &MethodCall<'_>) -> Result<String, Unsupported> {
+    let arg = single_arg(call)?;
+    migrate(arg).unwrap_or_else(|| Err(Unsupported::new(arg.span(), "expected a builder closure")))
+}
+
+/// Migrates closures of serenity 0.11 builders which only collect other builders, like
+/// `|o| o.create_option(|o| ..).add_option(option)`, into a `Vec` like
+/// `vec![CreateSelectMenuOption::new(..), option]`. `set_single_method`, like
+/// `.set_action_row(row)`, replaces all elements added so far by a single one.
+fn migrate_vec_builder(
+    closure: &BuilderClosure<'_>,
+    create_method: &str,
+    add_method: &str,
+    set_method: &str,
+    set_single_method: Option<&str>,
+) -> Result<String, Unsupported> {
+    let mut elements = Vec::new();
+    let mut all_elements = None;
+    for call in flat_call_chain(closure)? {
+        match &*call.method_name() {
+            name if name == create_method => elements.push(migrate_nested_closure(&call)?),
+            name if name == add_method => elements.push(single_arg(&call)?.source_code()),
+            name if name == set_method => {
+                elements.clear();
+                all_elements = Some(single_arg(&call)?.source_code());
+            }
+            name if Some(name) == set_single_method => {
+                elements = vec![single_arg(&call)?.source_code()];
+                all_elements = None;
+            }
+            other => {
+                return Err(Unsupported::new(call.span(), format!("unknown method `{other}`")));
+            }
+        }
+    }
+
+    match all_elements {
+        Some(_) if !elements.is_empty() => Err(Unsupported::new(
+            closure.expr.span(),
+            format!("expected nothing to be added after `{set_method}`"),
+        )),
+        Some(all_elements) => Ok(all_elements),
+        None => Ok(format!("vec![{}]", elements.join(", "))),
+    }
+}
+
+/// Migrates a builder closure into a builder value created by the given constructor
+fn migrate_closure(
+    closure: &BuilderClosure<'_>,
+    constructor: &Constructor,
+) -> Result<String, Unsupported> {
+    let body = closure.body.single_expr_block().unwrap_or(closure.body);
+    if let Some(block) = body.block() {
+        return migrate_block(closure, block, constructor);
+    }
+
+    let mut method_calls = builder_call_chain(body, &closure.param)
+        .ok_or_else(|| Unsupported::new(body.span(), "expected a method chain on the builder"))?;
+    check_unmigrated_uses(closure, &[chain_receiver(body, &method_calls).span()])?;
+    let required_args =
+        take_required_args(closure.expr, constructor, &mut [&mut method_calls], &[], &[])?;
+    Ok(format!("{}({required_args}){}", constructor.path, migrate_method_calls(method_calls)?))
+}
+
+/// Collects the method calls on the builder in a closure like `|b| b.a(..).b(..)` or
+/// `|b| { b.a(..); b.b(..) }`. Fails if the closure does anything else.
+fn flat_call_chain<'hir>(
+    closure: &BuilderClosure<'hir>,
+) -> Result<Vec<MethodCall<'hir>>, Unsupported> {
+    let expected_chain = |span| Unsupported::new(span, "expected only method calls on the builder");
+
+    let body = closure.body.single_expr_block().unwrap_or(closure.body);
+    let Some(block) = body.block() else {
+        return builder_call_chain(body, &closure.param).ok_or_else(|| expected_chain(body.span()));
+    };
+    let mut method_calls = Vec::new();
+    for stmt in block.stmts() {
+        let stmt_method_calls = stmt
+            .semi()
+            .and_then(|expr| builder_call_chain(expr, &closure.param))
+            .ok_or_else(|| expected_chain(stmt.span()))?;
+        method_calls.extend(stmt_method_calls);
+    }
+    let tail = block.expr().ok_or_else(|| expected_chain(block.span()))?;
+    method_calls.extend(
+        builder_call_chain(tail, &closure.param).ok_or_else(|| expected_chain(tail.span()))?,
+    );
+    Ok(method_calls)
+}
+
+/// Migrates a closure body with statements, like
+/// `{ let x = compute(); e.title(x); if admin { e.field(..); } e }`, into a block which binds the
+/// builder mutably and reassigns it in every builder statement, like
+/// `{ let x = compute(); let mut e = CreateEmbed::new(); e = e.title(x); if admin { e = e.field(..); } e }`
+fn migrate_block<'hir>(
+    closure: &BuilderClosure<'hir>,
+    block: Block<'hir>,
+    constructor: &Constructor,
+) -> Result<String, Unsupported> {
+    let param = &closure.param;
+    let binding = param.name().ok_or_else(|| {
+        Unsupported::new(param.span(), "expected the builder parameter to be a plain binding")
+    })?;
+    let top_level_stmts = block.stmts().collect::<Vec<_>>();
+
+    // Find statements like `e.title(x);`, anywhere in the closure body
+    let mut unconditional_stmts = Vec::new();
+    let mut conditional_stmts = Vec::new();
+    for stmt in block.stmts_recursive() {
+        let Some(expr) = stmt.semi() else { continue };
+        let Some(method_calls) = builder_call_chain(expr, param) else { continue };
+        if method_calls.is_empty() {
+            continue;
+        }
+        if top_level_stmts.iter().any(|top_level| top_level.span() == stmt.span()) {
+            unconditional_stmts.push((stmt, expr, method_calls));
+        } else {
+            conditional_stmts.push((stmt, expr, method_calls));
+        }
+    }
+    let tail = block.expr().ok_or_else(|| {
+        Unsupported::new(block.span(), "expected the builder to be returned at the end")
+    })?;
+    let mut tail_method_calls = builder_call_chain(tail, param)
+        .ok_or_else(|| Unsupported::new(tail.span(), "expected a method chain on the builder"))?;
+
+    // Position of the top-level statement containing the given code, or of the tail
+    let top_level_index = |span: rustc_span::Span| {
+        top_level_stmts
+            .iter()
+            .position(|top_level| top_level.span().contains(span))
+            .unwrap_or(top_level_stmts.len())
+    };
+    // The arguments of required setters like `e.title(x)` may refer to variables declared in the
+    // closure body, so the builder can't be declared before the last of them
+    let mut required_setters = Vec::new();
+    let mut removed_stmts = Vec::new();
+    // Required fields which are only set in every branch of an `if`/`else` statement, like
+    // `if public { c.name("pub"); } else { c.name("priv"); }`. The statement is removed and its
+    // branches become the constructor argument.
+    let mut set_in_every_branch = Vec::new();
+    for &field in constructor.required_fields {
+        let unconditional_calls =
+            unconditional_stmts.iter().flat_map(|(_, _, method_calls)| method_calls);
+        let last_call = unconditional_calls
+            .chain(&tail_method_calls)
+            .filter(|call| call.method_name() == field)
+            .last();
+        if let Some(call) = last_call {
+            required_setters.push(top_level_index(call.span()));
+            continue;
+        }
+        for (index, stmt) in top_level_stmts.iter().enumerate().rev() {
+            if let Some(arg) = stmt.expr().and_then(|expr| exhaustive_if_arg(expr, param, field)) {
+                required_setters.push(index);
+                removed_stmts.push(index);
+                set_in_every_branch.push((field, arg?));
+                break;
+            }
+        }
+    }
+    let last_required_setter = required_setters.iter().copied().max();
+
+    // Every other use of the builder would be left unchanged
+    let mut migrated = Iterator::chain(unconditional_stmts.iter(), conditional_stmts.iter())
+        .map(|(_, expr, method_calls)| chain_receiver(*expr, method_calls).span())
+        .collect::<Vec<_>>();
+    migrated.push(chain_receiver(tail, &tail_method_calls).span());
+    migrated.extend(removed_stmts.iter().map(|&index| top_level_stmts[index].span()));
+    check_unmigrated_uses(closure, &migrated)?;
+
+    let required_args = {
+        let mut unconditional_chains = unconditional_stmts
+            .iter_mut()
+            .map(|(_, _, method_calls)| method_calls)
+            .chain([&mut tail_method_calls])
+            .collect::<Vec<_>>();
+        let conditional_calls = conditional_stmts
+            .iter()
+            .flat_map(|(_, _, method_calls)| method_calls.iter().copied())
+            .collect::<Vec<_>>();
+        take_required_args(
+            closure.expr,
+            constructor,
+            &mut unconditional_chains,
+            &conditional_calls,
+            &set_in_every_branch,
+        )?
+    };
+
+    // The builder is declared just before it's first used, or after the variables which the
+    // required arguments refer to. Builder statements before that are moved after the declaration.
+    let first_use = Iterator::chain(unconditional_stmts.iter(), conditional_stmts.iter())
+        .map(|(stmt, _, _)| top_level_index(stmt.span()))
+        .min()
+        .unwrap_or(top_level_stmts.len());
+    let declaration_index = first_use.max(last_required_setter.unwrap_or(0));
+    let mut declaration = format!("let mut {binding} = {}({required_args});", constructor.path);
+
+    let source = block.source_code();
+    let inner = source.strip_prefix('{').unwrap_or(&source);
+    let indent = &inner[..inner.len() - inner.trim_start().len()];
+
+    let mut replacements = Vec::new();
+    for (stmt, expr, method_calls) in
+        Iterator::chain(unconditional_stmts.iter(), conditional_stmts.iter())
+    {
+        let index = top_level_index(stmt.span());
+        if removed_stmts.contains(&index) {
+            continue;
+        }
+        let is_top_level =
+            index < top_level_stmts.len() && top_level_stmts[index].span() == stmt.span();
+        if index < declaration_index && !is_top_level {
+            return Err(Unsupported::new(
+                stmt.span(),
+                "the builder is used conditionally before the variables which its required \
+                 fields are set from",
+            ));
+        }
+
+        let method_calls = migrate_method_calls(method_calls.clone())?;
+        if index < declaration_index || method_calls.is_empty() {
+            // Moved after the declaration, or all calls were moved into the constructor
+            if index < declaration_index && !method_calls.is_empty() {
+                declaration += &format!("{indent}{binding} = {binding}{method_calls};");
+            }
+            removed_stmts.push(index);
+        } else {
+            replacements.push((expr.span(), format!("{binding} = {binding}{method_calls}")));
+        }
     }
+    let method_calls = migrate_method_calls(tail_method_calls)?;
+    replacements.push((tail.span(), format!("{binding}{method_calls}")));
 
-    let mut body = closure.body();
-    if let Some(inner) = body.single_expr_block() {
-        body = inner;
+    let mut declaration_replaces_stmt = false;
+    for index in removed_stmts {
+        if index == declaration_index {
+            declaration_replaces_stmt = true;
+            continue;
+        }
+        let preceding_end = match index {
+            0 => block.span().lo() + rustc_span::BytePos(1),
+            _ => top_level_stmts[index - 1].span().hi(),
+        };
+        replacements.push((top_level_stmts[index].span().with_lo(preceding_end), String::new()));
     }
+    let declaration_point = match top_level_stmts.get(declaration_index) {
+        Some(stmt) => stmt.span(),
+        None => tail.span(),
+    };
+    if declaration_replaces_stmt {
+        replacements.push((declaration_point, declaration));
+    } else {
+        replacements.push((declaration_point.shrink_to_lo(), format!("{declaration}{indent}")));
+    }
+
+    Ok(block.source_code_with_replacements(replacements))
+}
 
-    let mut receiver = body;
+/// Splits `b.method1(..).method2(..)` into its method calls, if the chain starts at the builder
+/// parameter `b`
+fn builder_call_chain<'hir>(
+    expr: Expr<'hir>,
+    param: &Param<'hir>,
+) -> Option<Vec<MethodCall<'hir>>> {
+    let mut receiver = expr;
     let mut method_calls = Vec::new();
     while let Some(method_call) = receiver.method_call() {
         receiver = method_call.receiver();
         method_calls.insert(0, method_call);
     }
 
-    let mut replacement = format!("{}::new()", builder_type);
+    receiver.refers_to(param).then_some(method_calls)
+}
+
+/// Fails if the builder parameter is used anywhere in the closure other than as the receiver of
+/// the migrated method chains, whose spans are given, like in `helper(e);` or
+/// `let x = e.title(..);`. Such uses would be left unchanged.
+fn check_unmigrated_uses(
+    closure: &BuilderClosure<'_>,
+    migrated: &[rustc_span::Span],
+) -> Result<(), Unsupported> {
+    fn subexprs_with_closures(expr: Expr<'_>) -> Vec<Expr<'_>> {
+        let mut exprs = Vec::new();
+        for subexpr in expr.subexprs() {
+            exprs.push(subexpr);
+            // Closure bodies aren't part of the subexpressions
+            if let Some(closure) = subexpr.closure() {
+                exprs.extend(subexprs_with_closures(closure.body()));
+            }
+        }
+        exprs
+    }
+
+    let unmigrated = subexprs_with_closures(closure.body).into_iter().find(|expr| {
+        expr.refers_to(&closure.param)
+            && !migrated.iter().any(|migrated| migrated.contains(expr.span()))
+    });
+    match unmigrated {
+        Some(expr) => Err(Unsupported::new(
+            expr.span(),
+            "expected the builder to only be used to call its methods",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The receiver of a method chain like `b.method1(..).method2(..)`, which is `b`
+fn chain_receiver<'hir>(chain: Expr<'hir>, method_calls: &[MethodCall<'hir>]) -> Expr<'hir> {
+    method_calls.first().map_or(chain, |call| call.receiver())
+}
+
+/// Whether the builder closure calls the given method on the builder anywhere
+fn calls_method(closure: &BuilderClosure<'_>, method_name: &str) -> bool {
+    let body = closure.body.single_expr_block().unwrap_or(closure.body);
+    let mut chains = Vec::new();
+    if let Some(block) = body.block() {
+        chains.extend(block.stmts_recursive().iter().filter_map(|stmt| stmt.semi()));
+        chains.extend(block.expr());
+    } else {
+        chains.push(body);
+    }
+
+    chains
+        .into_iter()
+        .filter_map(|chain| builder_call_chain(chain, &closure.param))
+        .flatten()
+        .any(|call| call.method_name() == method_name)
+}
+
+/// Fields which must be passed to `::new()` of the given builder in serenity 0.12, in order
+fn required_fields(builder_type: &str) -> &'static [&'static str] {
+    match builder_type {
+        "AddMember" => &["access_token"],
+        "CreateCommand" => &["name"],
+        "CreateChannel" => &["name"],
+        "CreateButton" => &["custom_id"],
+        "CreateSelectMenu" => &["custom_id", "kind"],
+        "CreateSelectMenuOption" => &["label", "value"],
+        "CreateEmbedAuthor" => &["name"],
+        "CreateEmbedFooter" => &["text"],
+        "CreateModal" => &["custom_id", "title"],
+        "CreateStageInstance" => &["channel_id", "topic"],
+        "CreateThread" => &["name"],
+        "CreateWebhook" => &["name"],
+        "CreateQuickModal" => &["title"],
+        "CreateCommandOption" => &["kind", "name", "description"],
+        "CreateInputText" => &["style", "label", "custom_id"],
+        "CreateScheduledEvent" => &["kind", "name", "scheduled_start_time"],
+        "CreateSticker" => &["name", "tags", "description", "file"],
+        _ => &[],
+    }
+}
+
+/// Removes the setters of required fields from the unconditionally executed method chains and
+/// returns their arguments as a string for use in the constructor. If a required field is set
+/// multiple times, the last call wins. Setters of obsolete fields are removed as well.
+///
+/// Conditionally executed method calls are only used for a more helpful error message.
+fn take_required_args<'hir>(
+    closure: Expr<'hir>,
+    constructor: &Constructor,
+    unconditional_chains: &mut [&mut Vec<MethodCall<'hir>>],
+    conditional_calls: &[MethodCall<'hir>],
+    set_in_every_branch: &[(&str, String)],
+) -> Result<String, Unsupported> {
+    for chain in unconditional_chains.iter_mut() {
+        chain.retain(|call| !constructor.obsolete_fields.contains(&&*call.method_name()));
+    }
+
+    let constructor_path = &constructor.path;
+    let mut required_args = Vec::new();
+    for &field in constructor.required_fields {
+        let Some(call) = unconditional_chains
+            .iter()
+            .flat_map(|chain| chain.iter())
+            .filter(|call| call.method_name() == field)
+            .last()
+            .copied()
+        else {
+            if let Some((_, arg)) = set_in_every_branch.iter().find(|(set, _)| *set == field) {
+                required_args.push(arg.clone());
+                continue;
+            }
+            let message = if conditional_calls.iter().any(|call| call.method_name() == field) {
+                format!("`{constructor_path}` requires `{field}`, but it is only set conditionally")
+            } else {
+                format!("`{constructor_path}` requires `{field}`, but it is never set")
+            };
+            return Err(Unsupported::new(closure.span(), message));
+        };
+
+        for chain in unconditional_chains.iter_mut() {
+            chain.retain(|call| call.method_name() != field);
+        }
+        required_args.push(migrate_args(call)?);
+    }
+    Ok(required_args.join(", "))
+}
+
+/// The constructor argument for a required field which is set in every branch of an `if`/`else`
+/// statement doing nothing else, like `if public { c.name("pub"); } else { c.name("priv"); }` ->
+/// `if public { "pub" } else { "priv" }`
+///
+/// Returns None if `expr` is not such a statement.
+fn exhaustive_if_arg<'hir>(
+    expr: Expr<'hir>,
+    param: &Param<'hir>,
+    field: &str,
+) -> Option<Result<String, Unsupported>> {
+    // The setter call of each branch, with the condition leading to it
+    let mut branches = Vec::new();
+    let mut branch = expr;
+    loop {
+        let (condition, then, else_) = match branch.if_else() {
+            Some((condition, then, else_)) => (Some(condition), then, else_),
+            None => (None, branch, None),
+        };
+        let block = then.block()?;
+        if block.expr().is_some() {
+            return None;
+        }
+        let mut method_calls = Vec::new();
+        for stmt in block.stmts() {
+            method_calls.extend(builder_call_chain(stmt.semi()?, param)?);
+        }
+        if !method_calls.iter().all(|call| call.method_name() == field) {
+            return None;
+        }
+        branches.push((condition, *method_calls.last()?));
+        match (condition, else_) {
+            (Some(_), Some(else_)) => branch = else_,
+            (Some(_), None) => return None,
+            (None, _) => break,
+        }
+    }
+
+    let arg_types = branches.iter().map(|(_, call)| Some(single_arg(call).ok()?.type_()));
+    let arg_types = arg_types.collect::<Option<Vec<_>>>()?;
+    if arg_types.iter().any(|type_| *type_ != arg_types[0]) {
+        return Some(Err(Unsupported::new(
+            expr.span(),
+            format!("`{field}` is set to values of different types in the branches"),
+        )));
+    }
+
+    let mut output = String::new();
+    for (condition, call) in branches {
+        let arg = match migrate_args(call) {
+            Ok(arg) => arg,
+            Err(unsupported) => return Some(Err(unsupported)),
+        };
+        match condition {
+            Some(condition) => {
+                output += &format!("if {} {{ {arg} }} else ", condition.source_code())
+            }
+            None => output += &format!("{{ {arg} }}"),
+        }
+    }
+    Some(Ok(output))
+}
+
+/// Turns method calls into a string like `.method1(..).method2(..)`
+fn migrate_method_calls(method_calls: Vec<MethodCall<'_>>) -> Result<String, Unsupported> {
+    let mut output = String::new();
     for call in method_calls {
-        replacement += &format!(".{}(", call.method_name());
-        for arg in call.args() {
-            replacement += &format!("{}, ", arg.source_code().trim());
+        let mut method_name = call.method_name();
+        if call.args().any(|arg| BuilderClosure::new(arg).is_some()) {
+            if let Some(renamed) = renamed_setter(&method_name) {
+                method_name = renamed.to_owned();
+            }
         }
-        replacement += ")";
+        output += &format!(".{}({})", method_name, migrate_args(call)?);
     }
+    Ok(output)
+}
+
+/// How the argument of a setter whose argument type changed is converted
+#[derive(Clone, Copy)]
+enum Conversion {
+    /// Like `Some({arg}.to_string())`, which keeps the behavior of the old argument
+    Template(&'static str),
+    /// To a narrower integer type like `u16`. Values which don't fit panic instead of being
+    /// truncated.
+    Narrow(&'static str),
+}
+
+/// Setters whose argument type changed in serenity 0.12: builder, setter, conversion of the old
+/// argument
+static CHANGED_SETTER_ARGS: &[(&str, &str, Conversion)] = &[
+    ("GetMessages", "limit", Conversion::Template("{arg}.min(100) as u8")),
+    ("CreateChannel", "position", Conversion::Narrow("u16")),
+    ("CreateChannel", "rate_limit_per_user", Conversion::Narrow("u16")),
+    ("CreateThread", "rate_limit_per_user", Conversion::Narrow("u16")),
+    ("CreateSelectMenu", "min_values", Conversion::Narrow("u8")),
+    ("CreateSelectMenu", "max_values", Conversion::Narrow("u8")),
+    ("CreateInputText", "min_length", Conversion::Narrow("u16")),
+    ("CreateInputText", "max_length", Conversion::Narrow("u16")),
+    ("EditChannel", "bitrate", Conversion::Narrow("u32")),
+    ("EditChannel", "user_limit", Conversion::Narrow("u32")),
+    ("EditChannel", "position", Conversion::Narrow("u16")),
+    ("EditChannel", "rate_limit_per_user", Conversion::Narrow("u16")),
+    ("EditRole", "colour", Conversion::Narrow("u32")),
+    ("EditRole", "position", Conversion::Template("u16::from({arg})")),
+    ("EditRole", "unicode_emoji", Conversion::Template("Some({arg}.to_string())")),
+];
 
-    Some(replacement)
+/// Converts an argument like `n` in `.limit(n)` if the setter's argument type changed. Integer
+/// literals are left alone, because their type is inferred.
+fn convert_setter_arg(call: &MethodCall<'_>, arg: Expr<'_>) -> Option<String> {
+    if arg.is_integer_literal() {
+        return None;
+    }
+    let [crate_, .., builder_type] = &*call.self_type()?.adt()?.path().parts() else { return None };
+    let method_name = call.method_name();
+    let &(_, _, conversion) = CHANGED_SETTER_ARGS.iter().find(|(builder, setter, _)| {
+        crate_ == "serenity" && builder == builder_type && *setter == method_name
+    })?;
+    let arg = arg.source_code().trim().to_owned();
+    let template = match conversion {
+        Conversion::Template(template) => template,
+        Conversion::Narrow(type_) => {
+            return Some(format!(
+                "{type_}::try_from({arg}).expect(\"`{method_name}` is out of range\")"
+            ));
+        }
+    };
+    // Like `a.b.c()` or `"x"`, which don't need parentheses to be the receiver of a method call
+    let is_simple = arg.chars().all(|c| c.is_alphanumeric() || "_.()".contains(c))
+        || (arg.starts_with('"') && arg.ends_with('"'));
+    let arg = if is_simple { arg } else { format!("({arg})") };
+    Some(template.replace("{arg}", &arg))
+}
+
+/// Migrates nested builder closures like in `.embed(|e| e.title("x"))` and attachments like in
+/// `.add_file(AttachmentType::Path(path))`, converts arguments whose type changed, and copies
+/// everything else verbatim, apart from `Embed::fake` calls
+fn migrate_args(call: MethodCall<'_>) -> Result<String, Unsupported> {
+    let mut args = Vec::new();
+    for arg in call.args() {
+        if let Some(converted) = convert_setter_arg(&call, arg) {
+            args.push(converted);
+            continue;
+        }
+        let attachment = || Some(migrate_attachment(arg)?.map(|migration| migration.replacement));
+        args.push(
+            migrate(arg)
+                .or_else(attachment)
+                .unwrap_or_else(|| webhooks::migrate_nested_fake_embeds(arg))?
+                .trim()
+                .to_owned(),
+        );
+    }
+    Ok(args.join(", "))
 }
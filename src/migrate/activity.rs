@@ -0,0 +1,85 @@
+//! Migrates setting the bot's activity, which uses `ActivityData` instead of `Activity` in serenity
+//! 0.12
+
+use super::*;
+
+/// A migrated activity or presence call
+pub struct ActivityMigration {
+    /// The replacement for each changed part of the expression
+    pub replacements: Vec<(rustc_span::Span, String)>,
+    /// Why the error of `ActivityData::streaming` isn't propagated with `?`, if it isn't
+    pub fallback_reason: Option<&'static str>,
+}
+
+/// `Activity::playing("x")` -> `ActivityData::playing("x")`. `ActivityData::streaming` returns a
+/// `Result`, which is propagated with `?` if possible.
+fn migrate_activity_constructor(expr: Expr<'_>, call: Call<'_>) -> Option<ActivityMigration> {
+    let func = call.func();
+    let [crate_, .., type_name] = &*func.impl_self_type()?.adt()?.path().parts() else {
+        return None;
+    };
+    if !(crate_ == "serenity" && type_name == "Activity") {
+        return None;
+    }
+
+    let mut replacements = vec![migrate_type_path(func.path_qself()?)?];
+    let mut fallback_reason = None;
+    if func.path()?.parts().last()? == "streaming" {
+        let end = expr.source_span().shrink_to_hi();
+        if expr.enclosing_return_type().map_or(false, propagates_serenity_error) {
+            replacements.push((end, "?".to_owned()));
+        } else {
+            replacements.push((end, ".expect(\"invalid streaming URL\")".to_owned()));
+            fallback_reason = Some(
+                "`ActivityData::streaming` fails on invalid URLs in serenity 0.12, but the error \
+                 can't be propagated with `?` because the enclosing function doesn't return a \
+                 `Result` which a `serenity::Error` converts into",
+            );
+        }
+    }
+    Some(ActivityMigration { replacements, fallback_reason })
+}
+
+/// `ctx.set_activity(activity).await` -> `ctx.set_activity(Some(activity))`. `Context`'s presence
+/// methods aren't async anymore.
+fn migrate_presence_call(expr: Expr<'_>, call: MethodCall<'_>) -> Option<ActivityMigration> {
+    let [crate_, client, .., type_name] = &*call.self_type()?.adt()?.path().parts() else {
+        return None;
+    };
+    if !(crate_ == "serenity" && client == "client" && type_name == "Context") {
+        return None;
+    }
+
+    let mut replacements = Vec::new();
+    let mut fallback_reason = None;
+    match &*call.method_name() {
+        "set_activity" => {
+            // The activity is migrated here too, so that the replacements don't overlap
+            let activity = call.args().next()?;
+            let migrated = match migrate_activity(activity) {
+                Some(migration) => {
+                    fallback_reason = migration.fallback_reason;
+                    activity.source_code_with_replacements(migration.replacements)
+                }
+                None => activity.source_code(),
+            };
+            replacements.push((activity.source_span(), format!("Some({migrated})")));
+        }
+        "set_presence" => {}
+        _ => return None,
+    }
+    replacements.extend(expr.await_span().map(|span| (span, String::new())));
+    Some(ActivityMigration { replacements, fallback_reason })
+}
+
+/// Migrates `Activity` constructors and calls to `Context::set_activity` and
+/// `Context::set_presence`.
+///
+/// Returns None if `expr` is none of these.
+pub fn migrate_activity(expr: Expr<'_>) -> Option<ActivityMigration> {
+    if let Some(call) = expr.call() {
+        migrate_activity_constructor(expr, call)
+    } else {
+        migrate_presence_call(expr, expr.method_call()?)
+    }
+}
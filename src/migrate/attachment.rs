@@ -0,0 +1,110 @@
+//! Migrates `AttachmentType`, which was replaced by `CreateAttachment` in serenity 0.12. Its
+//! constructors which read a file are async and fallible.
+
+use super::*;
+
+/// A migrated `AttachmentType`
+pub struct AttachmentMigration {
+    pub replacement: String,
+    /// Why the error isn't propagated with `?`, if the migration falls back to `.expect(..)`
+    pub fallback_reason: Option<&'static str>,
+}
+
+/// The expression whose body the migrated attachment ends up in. Builder closures are replaced
+/// by builder values, so an attachment inside of them ends up in the body containing the closure.
+fn body_context(expr: Expr<'_>) -> Expr<'_> {
+    let mut context = expr;
+    for closure in expr.enclosing_closures() {
+        if BuilderClosure::new(closure).is_none() {
+            break;
+        }
+        context = closure;
+    }
+    context
+}
+
+/// `CreateAttachment::path(p)` -> `CreateAttachment::path(p).await?`, or `.await.expect(..)` if
+/// the error can't be propagated
+pub(super) fn await_constructor(
+    expr: Expr<'_>,
+    constructor: String,
+) -> Result<AttachmentMigration, Unsupported> {
+    let context = body_context(expr);
+    if !context.in_async_body() {
+        return Err(Unsupported::new(
+            expr.span(),
+            "`CreateAttachment` constructors which read a file are async in serenity 0.12, but \
+             this code isn't. Either make it async, or read the file yourself and use \
+             `CreateAttachment::bytes`.",
+        ));
+    }
+
+    if context.enclosing_return_type().map_or(false, propagates_serenity_error) {
+        return Ok(AttachmentMigration {
+            replacement: format!("{constructor}.await?"),
+            fallback_reason: None,
+        });
+    }
+    Ok(AttachmentMigration {
+        replacement: format!("{constructor}.await.expect(\"failed to read the attachment\")"),
+        fallback_reason: Some(
+            "reading the attachment can fail in serenity 0.12, but the error can't be propagated \
+             with `?` because the enclosing function doesn't return a `Result` which a \
+             `serenity::Error` converts into",
+        ),
+    })
+}
+
+/// `AttachmentType::Bytes { data, filename }` -> `CreateAttachment::bytes(data, filename)`
+fn migrate_bytes(expr: Expr<'_>) -> Option<Result<AttachmentMigration, Unsupported>> {
+    let [crate_, .., type_name, variant] = &*expr.struct_path()?.parts() else { return None };
+    if !(crate_ == "serenity" && type_name == "AttachmentType") {
+        return None;
+    }
+    let fields = expr.struct_fields()?;
+    let field = |name| {
+        let (_, value) = fields.iter().find(|(field_name, _)| field_name == name)?;
+        // The constructors take `impl Into<..>`, so `.into()` would leave the type ambiguous
+        match value.method_call() {
+            Some(call) if call.method_name() == "into" => Some(call.receiver().source_code()),
+            _ => Some(value.source_code()),
+        }
+    };
+
+    let (constructor, file) = match &**variant {
+        "Bytes" => {
+            let replacement =
+                format!("CreateAttachment::bytes({}, {})", field("data")?, field("filename")?);
+            return Some(Ok(AttachmentMigration { replacement, fallback_reason: None }));
+        }
+        "File" => ("file", field("file")?),
+        _ => return None,
+    };
+    let constructor = format!("CreateAttachment::{constructor}({file}, {})", field("filename")?);
+    Some(await_constructor(expr, constructor))
+}
+
+/// Migrates `AttachmentType::Path(p)` to `CreateAttachment::path(p).await?`, and likewise the
+/// `Bytes` and `File` variants. Returns the replacement of the whole expression.
+///
+/// Returns None if `expr` doesn't construct an `AttachmentType`.
+pub fn migrate_attachment(expr: Expr<'_>) -> Option<Result<AttachmentMigration, Unsupported>> {
+    let Some(call) = expr.call() else { return migrate_bytes(expr) };
+    let [crate_, .., type_name, variant] = &*call.func().path()?.parts() else { return None };
+    if !(crate_ == "serenity" && type_name == "AttachmentType") {
+        return None;
+    }
+
+    let [arg] = &*call.args().collect::<Vec<_>>() else { return None };
+    match &**variant {
+        "Path" => {
+            Some(await_constructor(expr, format!("CreateAttachment::path({})", arg.source_code())))
+        }
+        "Image" => Some(Err(Unsupported::new(
+            expr.span(),
+            "`AttachmentType::Image` has been replaced by `CreateAttachment::url`, which needs an \
+             `Http` to download the image",
+        ))),
+        _ => None,
+    }
+}
@@ -0,0 +1,103 @@
+//! Migrates uses of `CacheAndHttp`, which was removed in serenity 0.12. `Client` has separate
+//! `cache` and `http` fields instead.
+
+use super::*;
+
+/// Whether the type is serenity's `CacheAndHttp`
+fn is_cache_and_http(type_: Type<'_>) -> bool {
+    let Some(adt) = type_.adt() else { return false };
+    matches!(&*adt.path().parts(), [crate_, .., name] if crate_ == "serenity" && name == "CacheAndHttp")
+}
+
+/// Whether the type is `Arc<CacheAndHttp>`, which is migrated to `Arc<Http>`
+fn is_arc_cache_and_http(type_: Type<'_>) -> bool {
+    let Some(adt) = type_.adt() else { return false };
+    adt.path().parts() == ["alloc", "sync", "Arc"]
+        && type_.generic_args().pop().map_or(false, is_cache_and_http)
+}
+
+/// Whether the field access is `client.cache_and_http` on a serenity `Client`
+fn is_cache_and_http_field(field: &Field<'_>) -> bool {
+    if field.name() != "cache_and_http" {
+        return false;
+    }
+    let Some(adt) = field.base().type_().peel_refs().adt() else { return false };
+    matches!(&*adt.path().parts(), [crate_, .., client] if crate_ == "serenity" && client == "Client")
+}
+
+/// `with_cache_http(&client.cache_and_http)` -> `with_cache_http((&client.cache, &*client.http))`,
+/// where the argument has to implement `CacheHttp`
+fn migrate_cache_http_arg(expr: Expr<'_>) -> Option<String> {
+    let field = expr.borrowed()?.field().filter(is_cache_and_http_field)?;
+    let is_cache_http = expr.param_trait_bounds().iter().any(|bound| {
+        matches!(&*bound.parts(), [crate_, .., name] if crate_ == "serenity" && name == "CacheHttp")
+    });
+    if !is_cache_http {
+        return None;
+    }
+    let client = field.base().source_code();
+    Some(format!("(&{client}.cache, &*{client}.http)"))
+}
+
+/// `x.http` -> `x` for any other `CacheAndHttp` value `x`, whose `Arc<CacheAndHttp>` type is
+/// migrated to `Arc<Http>`. The cache has to be stored separately.
+fn migrate_stored_field(field: Field<'_>) -> Option<Result<String, Unsupported>> {
+    let base = field.base();
+    if !is_cache_and_http(base.adjusted_type().peel_refs()) {
+        return None;
+    }
+    match &*field.name() {
+        "http" if is_arc_cache_and_http(base.type_()) => Some(Ok(base.source_code())),
+        "http" => Some(Err(Unsupported::new(
+            field.span(),
+            "`CacheAndHttp` has been removed in serenity 0.12, so this value has to be replaced by \
+             the `Http` it contains",
+        ))),
+        "cache" => Some(Err(Unsupported::new(
+            field.span(),
+            "`CacheAndHttp` has been removed in serenity 0.12, so the cache has to be stored \
+             separately, like as an `Arc<Cache>`",
+        ))),
+        _ => None,
+    }
+}
+
+/// `client.cache_and_http.http` -> `client.http`, and `&client.cache_and_http` ->
+/// `(&client.cache, &*client.http)` where a `CacheHttp` is expected. Other uses of
+/// `client.cache_and_http` can't be migrated automatically. Fields of other `CacheAndHttp` values
+/// are migrated along with their type.
+///
+/// Returns None if `expr` doesn't access `client.cache_and_http` or a field of a `CacheAndHttp`.
+pub fn migrate_cache_and_http_field(expr: Expr<'_>) -> Option<Result<String, Unsupported>> {
+    if let Some(replacement) = migrate_cache_http_arg(expr) {
+        return Some(Ok(replacement));
+    }
+    let field = expr.field()?;
+    if let Some(cache_and_http) = field.base().field().filter(is_cache_and_http_field) {
+        let name = field.name();
+        if name == "http" || name == "cache" {
+            return Some(Ok(format!("{}.{name}", cache_and_http.base().source_code())));
+        }
+    }
+
+    if !is_cache_and_http_field(&field) {
+        return migrate_stored_field(field);
+    }
+    Some(Err(Unsupported::new(
+        field.span(),
+        "`Client::cache_and_http` has been split into `Client::cache` and `Client::http`. Where \
+         both are needed, `(&client.cache, &*client.http)` implements `CacheHttp`.",
+    )))
+}
+
+/// `Arc<CacheAndHttp>` -> `Arc<Http>`, in struct fields, function parameters and elsewhere.
+/// Returns the span of `CacheAndHttp` and its replacement.
+///
+/// Returns None if `type_` doesn't refer to `CacheAndHttp`.
+pub fn migrate_cache_and_http_type(type_: HirType<'_>) -> Option<(rustc_span::Span, String)> {
+    let [crate_, .., name] = &*type_.path()?.parts() else { return None };
+    if !(crate_ == "serenity" && name == "CacheAndHttp") {
+        return None;
+    }
+    Some((type_.source_span(), "Http".to_owned()))
+}
@@ -0,0 +1,80 @@
+//! Migrates cache getters, which return a guard referencing the cache in serenity 0.12 instead of
+//! a clone. Holding such a guard across an `.await` blocks the cache and makes the future `!Send`.
+
+use super::*;
+
+/// Getters which return a guard in serenity 0.12: type they're implemented on, method names
+static GUARD_GETTERS: &[(&str, &[&str])] = &[
+    ("Cache", &[
+        "guild",
+        "channel",
+        "member",
+        "message",
+        "role",
+        "user",
+        "category",
+        "current_user",
+    ]),
+    ("ChannelId", &["to_channel_cached"]),
+    ("GuildId", &["to_guild_cached"]),
+    ("UserId", &["to_user_cached"]),
+];
+
+/// The outermost method call chain starting at `expr`, like `cache.guild(id).unwrap()` for
+/// `cache.guild(id)`
+fn method_chain_end(expr: Expr<'_>) -> Expr<'_> {
+    let mut end = expr;
+    while let Some(parent) = end.parent() {
+        if parent.method_call().map_or(true, |call| call.receiver() != end) {
+            break;
+        }
+        end = parent;
+    }
+    end
+}
+
+/// Whether the getter's return value is bound to a variable whose scope contains an `.await`
+/// after the getter call. The guard is only dropped at the end of the scope, so it would be held
+/// across the `.await`.
+fn held_across_await(getter_chain: Expr<'_>) -> bool {
+    let Some(scope) = getter_chain.binding_scope() else { return false };
+    getter_chain.enclosing_body_exprs().iter().any(|expr| {
+        expr.is_await()
+            && scope.contains(expr.span())
+            && expr.span().lo() >= getter_chain.span().hi()
+    })
+}
+
+/// `let guild = cache.guild(id).unwrap(); x.await; guild.name` ->
+/// `let guild = cache.guild(id).map(|g| g.clone()).unwrap(); x.await; guild.name`. Returns where
+/// to insert the clone. All other uses of the getters can't be checked automatically.
+///
+/// Returns None if `expr` is not a call to a getter which returns a guard in serenity 0.12.
+pub fn migrate_cache_getter(
+    expr: Expr<'_>,
+) -> Option<Result<(rustc_span::Span, String), Unsupported>> {
+    let call = expr.method_call()?;
+    let [crate_, .., type_name] = &*call.self_type()?.adt()?.path().parts() else { return None };
+    let name = call.method_name();
+    let is_getter = GUARD_GETTERS
+        .iter()
+        .any(|(type_, methods)| type_ == type_name && methods.contains(&&*name));
+    if !(crate_ == "serenity" && is_getter) {
+        return None;
+    }
+
+    if !held_across_await(method_chain_end(expr)) {
+        return Some(Err(Unsupported::new(
+            expr.span(),
+            format!(
+                "`{name}` returns a guard in serenity 0.12, which mustn't be held across an \
+                 `.await`"
+            ),
+        )));
+    }
+
+    let is_option =
+        expr.type_().adt().map_or(false, |adt| adt.path().parts() == ["core", "option", "Option"]);
+    let clone = if is_option { ".map(|g| g.clone())" } else { ".clone()" };
+    Some(Ok((expr.source_span().shrink_to_hi(), clone.to_owned())))
+}
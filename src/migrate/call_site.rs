@@ -0,0 +1,82 @@
+//! Migrates calls to serenity functions which were renamed in 0.12, along with their builder
+//! arguments
+
+use super::*;
+
+const GUILDS: &[&str] = &["Guild", "GuildId", "PartialGuild"];
+const CHANNELS: &[&str] = &["ChannelId", "GuildChannel"];
+const INTERACTIONS: &[&str] =
+    &["ApplicationCommandInteraction", "MessageComponentInteraction", "ModalSubmitInteraction"];
+
+/// Functions which were renamed in serenity 0.12: types they're implemented on, old name, new name
+static RENAMED_FNS: &[(&[&str], &str, &str)] = &[
+    (&["Command"], "create_global_application_command", "create_global_command"),
+    (&["Command"], "set_global_application_commands", "set_global_commands"),
+    (&["Command"], "edit_global_application_command", "edit_global_command"),
+    (GUILDS, "create_application_command", "create_command"),
+    (GUILDS, "set_application_commands", "set_commands"),
+    (GUILDS, "edit_application_command", "edit_command"),
+    (CHANNELS, "create_public_thread", "create_thread_from_message"),
+    (CHANNELS, "create_private_thread", "create_thread"),
+    (INTERACTIONS, "get_interaction_response", "get_response"),
+    (INTERACTIONS, "create_interaction_response", "create_response"),
+    (INTERACTIONS, "edit_original_interaction_response", "edit_response"),
+    (INTERACTIONS, "delete_original_interaction_response", "delete_response"),
+    (INTERACTIONS, "create_followup_message", "create_followup"),
+    (INTERACTIONS, "edit_followup_message", "edit_followup"),
+    (INTERACTIONS, "delete_followup_message", "delete_followup"),
+    (INTERACTIONS, "get_followup_message", "get_followup"),
+];
+
+/// Setters which renamed functions called on their builder in serenity 0.11, but which must be
+/// called explicitly in 0.12: old name, setter name, setter call
+static IMPLICIT_SETTERS: &[(&str, &str, &str)] =
+    &[("create_private_thread", "kind", ".kind(ChannelType::PrivateThread)")];
+
+/// Renames calls like `Command::set_global_application_commands(&http, |c| ..)` or
+/// `interaction.create_interaction_response(&http, |r| ..)` and migrates their builder closure
+/// arguments. Functions are matched by the type they're implemented on, so that the rename only
+/// applies to serenity's types. Returns the replacement for each changed part of the call.
+///
+/// Returns None if `expr` is not a call to a renamed function.
+pub fn migrate_call_site(
+    expr: Expr<'_>,
+) -> Option<Result<Vec<(rustc_span::Span, String)>, Unsupported>> {
+    let (self_type, name, name_span, args) = if let Some(call) = expr.method_call() {
+        let args = call.args().collect::<Vec<_>>();
+        (call.self_type()?, call.method_name(), call.method_name_span(), args)
+    } else if let Some(call) = expr.call() {
+        let func = call.func();
+        let name = func.path()?.parts().pop()?;
+        (func.impl_self_type()?, name, func.path_name_span()?, call.args().collect())
+    } else {
+        return None;
+    };
+
+    let [crate_, .., type_name] = &*self_type.adt()?.path().parts() else { return None };
+    if crate_ != "serenity" {
+        return None;
+    }
+    let &(_, _, new_name) = RENAMED_FNS
+        .iter()
+        .find(|(types, old_name, _)| types.contains(&&**type_name) && *old_name == name)?;
+
+    let mut replacements = vec![(name_span, new_name.to_owned())];
+    let implicit_setter = IMPLICIT_SETTERS.iter().find(|(old_name, ..)| *old_name == name);
+    for arg in args {
+        match migrate(arg) {
+            Some(Ok(mut replacement)) => {
+                if let Some((_, setter, call)) = implicit_setter {
+                    let closure = BuilderClosure::new(arg);
+                    if !closure.map_or(false, |closure| calls_method(&closure, setter)) {
+                        replacement += call;
+                    }
+                }
+                replacements.push((arg.span(), replacement));
+            }
+            Some(Err(unsupported)) => return Some(Err(unsupported)),
+            None => {}
+        }
+    }
+    Some(Ok(replacements))
+}
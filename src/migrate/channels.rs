@@ -0,0 +1,152 @@
+//! Migrates matching on `Channel`, whose `Category` variant was removed in serenity 0.12.
+//! Categories are `Channel::Guild` with `kind == ChannelType::Category` instead, which is also
+//! why `Guild::channels` can contain `GuildChannel`s directly.
+
+use super::*;
+
+fn is_channel_variant(pat: &Pat<'_>, variant: &str) -> bool {
+    let Some(path) = pat.path() else { return false };
+    matches!(&*path.parts(), [crate_, .., enum_, name] if crate_ == "serenity" && enum_ == "Channel" && name == variant)
+}
+
+/// Whether the expression reads from `guild.channels`, like `guild.channels.get(&id)` or
+/// `&guild.channels[&id]`
+fn reads_guild_channels(mut expr: Expr<'_>) -> bool {
+    loop {
+        if let Some(field) = expr.field() {
+            let Some(adt) = field.base().type_().peel_refs().adt() else { return false };
+            return field.name() == "channels"
+                && matches!(&*adt.path().parts(), [crate_, .., guild] if crate_ == "serenity" && guild == "Guild");
+        }
+        expr = match expr.method_call() {
+            Some(call) => call.receiver(),
+            None => match expr.index_base().or_else(|| expr.borrowed()).or_else(|| expr.deref()) {
+                Some(inner) => inner,
+                None => return false,
+            },
+        };
+    }
+}
+
+/// `if let Some(Channel::Guild(channel)) = guild.channels.get(&id)` ->
+/// `if let Some(channel) = guild.channels.get(&id)`. If the pattern becomes irrefutable, like in
+/// `if let Channel::Guild(channel) = &guild.channels[&id] { .. }`, the `if let` is replaced by a
+/// block starting with `let channel = &guild.channels[&id];`. Returns the replacement for each
+/// changed part of the code.
+///
+/// Returns None if `pat` is not a `Channel::Guild` pattern on a value from `Guild::channels`.
+pub fn migrate_guild_channel_pat(
+    pat: Pat<'_>,
+) -> Option<Result<Vec<(rustc_span::Span, String)>, Unsupported>> {
+    let matched_expr = pat.matched_expr()?;
+    if !is_channel_variant(&pat, "Guild") || !reads_guild_channels(matched_expr) {
+        return None;
+    }
+    let [channel] = &*pat.tuple_struct_fields()? else { return None };
+    let Some((if_, is_while)) = pat.if_let() else {
+        return Some(Ok(vec![(pat.source_span(), channel.source_code())]));
+    };
+
+    let unsupported = |message| Some(Err(Unsupported::new(if_.span(), message)));
+    if is_while {
+        return unsupported(
+            "the pattern always matches, so this loop would only end with `break` or `return`",
+        );
+    }
+    let (_, then, else_) = if_.if_else()?;
+    let is_trivial = |else_: Expr<'_>| {
+        else_
+            .block()
+            .map_or(false, |block| block.stmts().next().is_none() && block.expr().is_none())
+    };
+    if !else_.map_or(true, is_trivial) {
+        return unsupported(
+            "the pattern always matches, so the `else` branch would never run. Move its code \
+             elsewhere.",
+        );
+    }
+
+    let then_span = then.source_span();
+    let source = then.source_code();
+    let inner = source.strip_prefix('{').unwrap_or(&source);
+    let indent = &inner[..inner.len() - inner.trim_start().len()];
+    let header = if_.source_span().with_hi(then_span.lo() + rustc_span::BytePos(1));
+    let binding =
+        format!("{{{indent}let {} = {};", channel.source_code(), matched_expr.source_code());
+    Some(Ok(vec![(header, binding), (if_.source_span().with_lo(then_span.hi()), String::new())]))
+}
+
+/// The `Channel` variant pattern within the arm, like `Channel::Guild(c)` in
+/// `Some(Channel::Guild(c))`
+fn variant_pat<'hir>(arm: &Arm<'hir>, variant: &str) -> Option<Pat<'hir>> {
+    arm.pat().subpatterns().into_iter().find(|pat| is_channel_variant(pat, variant))
+}
+
+/// `Channel::Category(category) => ..` ->
+/// `Channel::Guild(category) if category.kind == ChannelType::Category => ..`, or
+/// `Some(category) if category.kind == ChannelType::Category => ..` for
+/// `Some(Channel::Category(category))` on a value from `Guild::channels`
+fn migrate_category_arm(
+    arm: Arm<'_>,
+    category: Pat<'_>,
+) -> Result<(rustc_span::Span, String), Unsupported> {
+    let unsupported = |message| Unsupported::new(category.span(), message);
+    let fields = category.tuple_struct_fields().unwrap_or_default();
+    let [field] = &*fields else {
+        return Err(unsupported("expected a `Channel::Category` pattern with a single field"));
+    };
+    let binding = if let Some(binding) = field.binding_name() {
+        binding
+    } else if field.is_wildcard() {
+        "category".to_owned()
+    } else {
+        return Err(unsupported("expected the category to be bound to a variable"));
+    };
+
+    let replacements = if arm.pat().matched_expr().map_or(false, reads_guild_channels) {
+        vec![(category.span(), binding.clone())]
+    } else {
+        let name_span = category.path_name_span().unwrap_or(category.span());
+        vec![(name_span, "Guild".to_owned()), (field.span(), binding.clone())]
+    };
+    let mut replacement = format!(
+        "{} if {binding}.kind == ChannelType::Category",
+        arm.pat().source_code_with_replacements(replacements)
+    );
+    let mut span = arm.pat().source_span();
+    if arm.has_guard() {
+        let guard = arm.guard().ok_or_else(|| unsupported("`if let` guards are not supported"))?;
+        replacement += &format!(" && ({})", guard.source_code());
+        span = span.to(guard.source_span());
+    }
+    Ok((span, replacement))
+}
+
+/// Migrates each `Channel::Category` arm of a `match` expression. Categories are matched by
+/// `Channel::Guild` arms in serenity 0.12, so the category arm must come first.
+///
+/// Returns None if `expr` is not a `match` with `Channel::Category` arms.
+pub fn migrate_category_arms(
+    expr: Expr<'_>,
+) -> Option<Vec<Result<(rustc_span::Span, String), Unsupported>>> {
+    let arms = expr.match_arms()?;
+    let migrations = arms
+        .iter()
+        .enumerate()
+        .filter_map(|(i, arm)| Some((i, arm, variant_pat(arm, "Category")?)))
+        .map(|(i, arm, category)| {
+            let catches_categories = arms[..i]
+                .iter()
+                .any(|earlier| !earlier.has_guard() && variant_pat(earlier, "Guild").is_some());
+            if catches_categories {
+                return Err(Unsupported::new(
+                    category.span(),
+                    "this arm must be moved before the `Channel::Guild` arm, which matches \
+                     categories in serenity 0.12",
+                ));
+            }
+            migrate_category_arm(*arm, category)
+        })
+        .collect::<Vec<_>>();
+    (!migrations.is_empty()).then_some(migrations)
+}
@@ -0,0 +1,98 @@
+//! Migrates collectors, whose builders were merged into the collectors themselves in serenity
+//! 0.12. Collectors are created from a `ShardMessenger` like `&ctx.shard`, and turned into a
+//! stream with `.stream()` instead of `.build()`.
+
+use super::*;
+
+fn is_collector(type_: Type<'_>) -> bool {
+    let Some(adt) = type_.adt() else { return false };
+    matches!(&*adt.path().parts(), [crate_, module, ..] if crate_ == "serenity" && module == "collector")
+}
+
+fn is_context(type_: Type<'_>) -> bool {
+    let Some(adt) = type_.peel_refs().adt() else { return false };
+    matches!(&*adt.path().parts(), [crate_, client, .., context] if crate_ == "serenity" && client == "client" && context == "Context")
+}
+
+/// The argument a collector is created from, like `&ctx` in `msg.await_reply(&ctx)` or
+/// `MessageCollectorBuilder::new(&ctx)`
+///
+/// Returns None if `expr` doesn't create a collector.
+fn shard_messenger_arg(expr: Expr<'_>) -> Option<Expr<'_>> {
+    let arg = if let Some(call) = expr.method_call() {
+        // Setters like `.timeout(..)` return the collector they're called on
+        if is_collector(call.receiver().type_()) {
+            return None;
+        }
+        call.args().next()?
+    } else {
+        let call = expr.call()?;
+        if call.func().path()?.parts().last()? != "new" {
+            return None;
+        }
+        call.args().next()?
+    };
+    is_collector(expr.type_()).then_some(arg)
+}
+
+/// `msg.author.await_reply(&ctx)` -> `msg.author.await_reply(&ctx.shard)`, and
+/// `MessageCollectorBuilder::new(&ctx).collect_limit(3).build()` ->
+/// `MessageCollectorBuilder::new(&ctx.shard).stream().take(3)`. The collector types themselves
+/// are renamed like other paths. Returns the replacement for each changed part of the method
+/// call chain.
+///
+/// Returns None if `expr` doesn't create a collector.
+pub fn migrate_collector(
+    expr: Expr<'_>,
+) -> Option<Result<Vec<(rustc_span::Span, String)>, Unsupported>> {
+    let arg = shard_messenger_arg(expr)?;
+    let mut replacements = Vec::new();
+    if is_context(arg.type_()) {
+        let context = arg.borrowed().unwrap_or(arg);
+        replacements.push((arg.source_span(), format!("&{}.shard", context.source_code())));
+    }
+
+    let mut collect_limit = None;
+    let mut chain_end = expr;
+    while let Some(parent) = chain_end.parent() {
+        let Some(call) = parent.method_call().filter(|call| call.receiver() == chain_end) else {
+            break;
+        };
+        chain_end = parent;
+        match &*call.method_name() {
+            "build" => {
+                replacements.push((call.method_name_span(), "stream".to_owned()));
+                if let Some(limit) = collect_limit.take() {
+                    replacements
+                        .push((parent.source_span().shrink_to_hi(), format!(".take({limit})")));
+                }
+            }
+            "collect_limit" => {
+                let [limit] = &*call.args().collect::<Vec<_>>() else { return None };
+                collect_limit = Some(limit.source_code());
+                let receiver_end = call.receiver().source_span().hi();
+                replacements.push((parent.source_span().with_lo(receiver_end), String::new()));
+            }
+            "filter_limit" => {
+                return Some(Err(Unsupported::new(
+                    call.method_name_span(),
+                    "`filter_limit` has been removed in serenity 0.12. Count the items passing \
+                     `.filter(..)` instead.",
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    if replacements.is_empty() {
+        return None;
+    }
+    if collect_limit.is_some() {
+        return Some(Err(Unsupported::new(
+            expr.span(),
+            "`collect_limit` has been removed in serenity 0.12. Limit the stream returned by \
+             `.stream()` with `.take(..)` instead.",
+        )));
+    }
+    Some(Ok(replacements))
+}
@@ -0,0 +1,141 @@
+//! Migrates message components, whose builders were restructured in serenity 0.12
+
+use super::*;
+
+/// `|r| r.create_button(|b| ..).add_button(button)` ->
+/// `CreateActionRow::Buttons(vec![CreateButton::new(..), button])`
+pub(super) fn migrate_action_row(closure: &BuilderClosure<'_>) -> Result<String, Unsupported> {
+    let mut buttons = Vec::new();
+    let mut select_menus = Vec::new();
+    let mut input_texts = Vec::new();
+    for call in flat_call_chain(closure)? {
+        match &*call.method_name() {
+            "create_button" => buttons.push(migrate_nested_closure(&call)?),
+            "add_button" => buttons.push(single_arg(&call)?.source_code()),
+            "create_select_menu" => select_menus.push(migrate_nested_closure(&call)?),
+            "add_select_menu" => select_menus.push(single_arg(&call)?.source_code()),
+            "create_input_text" => input_texts.push(migrate_nested_closure(&call)?),
+            "add_input_text" => input_texts.push(single_arg(&call)?.source_code()),
+            other => {
+                return Err(Unsupported::new(call.span(), format!("unknown method `{other}`")));
+            }
+        }
+    }
+
+    // In serenity 0.12, an action row can't mix different kinds of components
+    match (&*buttons, &*select_menus, &*input_texts) {
+        ([_, ..], [], []) => Ok(format!("CreateActionRow::Buttons(vec![{}])", buttons.join(", "))),
+        ([], [select_menu], []) => Ok(format!("CreateActionRow::SelectMenu({select_menu})")),
+        ([], [], [input_text]) => Ok(format!("CreateActionRow::InputText({input_text})")),
+        _ => Err(Unsupported::new(
+            closure.expr.span(),
+            "expected an action row with either only buttons, a single select menu or a single \
+             input text",
+        )),
+    }
+}
+
+/// Link buttons have their own constructor in serenity 0.12 and no style
+pub(super) fn migrate_button(closure: &BuilderClosure<'_>) -> Result<String, Unsupported> {
+    let constructor = if calls_method(closure, "url") {
+        Constructor {
+            path: "CreateButton::new_link".into(),
+            required_fields: &["url"],
+            obsolete_fields: &["style"],
+        }
+    } else {
+        Constructor::new("CreateButton")
+    };
+    migrate_closure(closure, &constructor)
+}
+
+/// `|m| m.custom_id(..).options(|o| ..).placeholder(..)` ->
+/// `CreateSelectMenu::new(.., CreateSelectMenuKind::String { options: vec![..] }).placeholder(..)`
+pub(super) fn migrate_select_menu(closure: &BuilderClosure<'_>) -> Result<String, Unsupported> {
+    let mut custom_id = None;
+    let mut kind = "String".to_owned();
+    let mut channel_types = None;
+    let mut options = None;
+    let mut optional_setters = String::new();
+    for call in flat_call_chain(closure)? {
+        match &*call.method_name() {
+            "custom_id" => custom_id = Some(migrate_args(call)?),
+            "kind" => {
+                let (variant, variant_channel_types) = select_menu_kind(&call)?;
+                kind = variant;
+                // Like `SelectMenuType::ChannelSelect`, after `.channel_types(..)`
+                if variant_channel_types.is_some() {
+                    channel_types = variant_channel_types;
+                }
+            }
+            "channel_types" => channel_types = Some(format!("Some({})", migrate_args(call)?)),
+            "options" => options = Some(migrate_nested_closure(&call)?),
+            other => optional_setters += &format!(".{}({})", other, migrate_args(call)?),
+        }
+    }
+
+    let unsupported = |message: &str| Unsupported::new(closure.expr.span(), message);
+    let custom_id = custom_id.ok_or_else(|| {
+        unsupported("`CreateSelectMenu::new` requires `custom_id`, but it is never set")
+    })?;
+    if kind != "Channel" && channel_types.is_some() {
+        return Err(unsupported("channel types are only supported by channel select menus"));
+    }
+    let kind = match &*kind {
+        "String" => {
+            let options = options
+                .ok_or_else(|| unsupported("expected options for the string select menu"))?;
+            format!("CreateSelectMenuKind::String {{ options: {options} }}")
+        }
+        "User" => "CreateSelectMenuKind::User { default_users: None }".to_owned(),
+        "Role" => "CreateSelectMenuKind::Role { default_roles: None }".to_owned(),
+        "Mentionable" => {
+            "CreateSelectMenuKind::Mentionable { default_users: None, default_roles: None }"
+                .to_owned()
+        }
+        "Channel" => {
+            let channel_types = channel_types.unwrap_or_else(|| "None".to_owned());
+            format!(
+                "CreateSelectMenuKind::Channel {{ channel_types: {channel_types}, default_channels: None }}"
+            )
+        }
+        other => return Err(unsupported(&format!("unknown select menu type `{other}`"))),
+    };
+    Ok(format!("CreateSelectMenu::new({custom_id}, {kind}){optional_setters}"))
+}
+
+/// Finds the select menu type in a call like `.kind(SelectMenuType::User)` or
+/// `.kind(SelectMenuType::Channel(channel_types))`. Returns the name of the corresponding
+/// `CreateSelectMenuKind` variant and the channel types as an `Option`, if any are given.
+fn select_menu_kind(call: &MethodCall<'_>) -> Result<(String, Option<String>), Unsupported> {
+    let arg = single_arg(call)?;
+    let (path, channel_types) = match arg.call() {
+        Some(variant_call) => {
+            let channel_types = variant_call.args().next().and_then(|channel_types| {
+                let is_option = channel_types
+                    .type_()
+                    .adt()
+                    .map_or(false, |adt| adt.path().parts() == ["core", "option", "Option"]);
+                let is_none = channel_types
+                    .path()
+                    .map_or(false, |path| path.parts() == ["core", "option", "Option", "None"]);
+                if is_none {
+                    None
+                } else if is_option {
+                    Some(channel_types.source_code())
+                } else {
+                    Some(format!("Some({})", channel_types.source_code()))
+                }
+            });
+            (variant_call.func().path(), channel_types)
+        }
+        None => (arg.path(), None),
+    };
+    let variant = path
+        .and_then(|path| path.parts().pop())
+        .ok_or_else(|| Unsupported::new(arg.span(), "expected a select menu type variant"))?;
+
+    // `ComponentType` variants are suffixed with `Select`
+    let variant = variant.strip_suffix("Select").unwrap_or(&variant).to_owned();
+    Ok((variant, channel_types))
+}
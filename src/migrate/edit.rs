@@ -0,0 +1,47 @@
+//! Migrates `Role::edit` and `Member::edit`, which took `&self` and returned the edited value in
+//! serenity 0.11. In 0.12, they take `&mut self` and update the value in place instead.
+
+use super::*;
+
+/// Methods whose receiver changed to `&mut self`: type they're implemented on, method name, and
+/// the equivalent call on the ID, which still returns the edited value
+static EDIT_CALLS: &[(&str, &str, &str)] = &[
+    ("Role", "edit", "{receiver}.guild_id.edit_role({http}, {receiver}.id, {builder})"),
+    ("Member", "edit", "{receiver}.guild_id.edit_member({http}, {receiver}.user.id, {builder})"),
+];
+
+/// `role.edit(&http, |r| r.name("x"))` ->
+/// `role.guild_id.edit_role(&http, role.id, EditRole::new().name("x"))`. Returns the replacement
+/// of the call.
+///
+/// Returns None if `expr` is not a call to one of these methods.
+pub fn migrate_edit_call(expr: Expr<'_>) -> Option<Result<String, Unsupported>> {
+    let call = expr.method_call()?;
+    let [crate_, .., type_name] = &*call.self_type()?.adt()?.path().parts() else { return None };
+    let method_name = call.method_name();
+    let &(_, _, replacement) = EDIT_CALLS.iter().find(|(type_, method, _)| {
+        crate_ == "serenity" && type_ == type_name && *method == method_name
+    })?;
+    let [http, closure] = &*call.args().collect::<Vec<_>>() else { return None };
+    let builder = match migrate(*closure)? {
+        Ok(builder) => builder,
+        Err(unsupported) => return Some(Err(unsupported)),
+    };
+
+    // The receiver is repeated, so it must be cheap and free of side effects
+    let receiver = call.receiver().source_code();
+    if !receiver.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+        return Some(Err(Unsupported::new(
+            call.receiver().span(),
+            format!(
+                "`{type_name}::edit` takes `&mut self` in serenity 0.12. Assign the {} to a \
+                 variable first, or call the method on its ID.",
+                type_name.to_lowercase()
+            ),
+        )));
+    }
+    Some(Ok(replacement
+        .replace("{receiver}", &receiver)
+        .replace("{http}", &http.source_code())
+        .replace("{builder}", &builder)))
+}
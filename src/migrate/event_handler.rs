@@ -0,0 +1,92 @@
+//! Migrates `EventHandler` methods whose parameters changed in serenity 0.12
+
+use super::*;
+
+/// Inserts a statement at the start of the function body which rebinds the given parameter, if
+/// the parameter is used. Parameters starting with an underscore are assumed to be unused.
+fn rebind_param(
+    method: ImplFn<'_>,
+    param: &Param<'_>,
+    rebinding: impl FnOnce(&str) -> String,
+) -> Option<(rustc_span::Span, String)> {
+    let name = param.name().filter(|name| !name.starts_with('_'))?;
+    let body = method.body_block()?;
+    let first_stmt = match body.stmts().next() {
+        Some(stmt) => stmt.source_span(),
+        None => body.expr()?.source_span(),
+    };
+
+    let source = body.source_code();
+    let inner = source.strip_prefix('{').unwrap_or(&source);
+    let indent = &inner[..inner.len() - inner.trim_start().len()];
+    Some((first_stmt.shrink_to_lo(), format!("{}{indent}", rebinding(&name))))
+}
+
+/// A migrated `EventHandler` method
+pub struct EventHandlerMigration {
+    pub replacements: Vec<(rustc_span::Span, String)>,
+    /// How the migrated method may behave differently, if it may
+    pub caveat: Option<&'static str>,
+}
+
+/// `async fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool)` ->
+/// `async fn guild_create(&self, ctx: Context, guild: Guild, is_new: Option<bool>)`, and likewise
+/// for the other methods whose parameters changed. Methods with the signature they have without
+/// serenity's `cache` feature are migrated too.
+///
+/// Returns None if `method` is not an `EventHandler` method whose parameters changed.
+pub fn migrate_event_handler_fn(method: ImplFn<'_>) -> Option<EventHandlerMigration> {
+    let trait_path = method.trait_path()?.parts();
+    if !(trait_path.first()? == "serenity" && trait_path.last()? == "EventHandler") {
+        return None;
+    }
+
+    let member = "serenity::model::guild::Member";
+    let mut replacements = Vec::new();
+    let mut caveat = None;
+    match (&*method.name(), &*method.params()) {
+        ("guild_create", [_ctx, _guild, is_new]) => {
+            replacements.push((is_new.hir_type().source_span(), "Option<bool>".to_owned()));
+            replacements.extend(rebind_param(method, is_new, |name| {
+                format!("let {name} = {name}.unwrap_or(false);")
+            }));
+        }
+        ("guild_create", [_ctx, guild]) => {
+            replacements
+                .push((guild.source_span().shrink_to_hi(), ", _is_new: Option<bool>".into()));
+        }
+        ("guild_member_update", [_ctx, _old_if_available, new]) => {
+            let new_type = new.hir_type();
+            replacements
+                .push((new_type.source_span(), format!("Option<{}>", new_type.source_code())));
+            replacements.push((
+                new.source_span().shrink_to_hi(),
+                ", _event: serenity::model::event::GuildMemberUpdateEvent".to_owned(),
+            ));
+            let rebinding = rebind_param(method, new, |name| {
+                format!("let Some({name}) = {name} else {{ return }};")
+            });
+            if rebinding.is_some() {
+                caveat = Some(
+                    "the new member is only available if it's cached in serenity 0.12. \
+                     Otherwise, this returns early, before any other code in the method runs.",
+                );
+            }
+            replacements.extend(rebinding);
+        }
+        ("guild_member_update", [_ctx, event]) => {
+            replacements.push((
+                event.source_span().shrink_to_lo(),
+                format!("_old_if_available: Option<{member}>, _new: Option<{member}>, "),
+            ));
+        }
+        ("guild_member_removal", [_ctx, _guild_id, user]) => {
+            replacements.push((
+                user.source_span().shrink_to_hi(),
+                format!(", _member_data_if_available: Option<{member}>"),
+            ));
+        }
+        _ => return None,
+    }
+    Some(EventHandlerMigration { replacements, caveat })
+}
@@ -0,0 +1,83 @@
+//! Migrates configuring the standard framework. `StandardFramework::configure` takes a
+//! `Configuration` value and `&self` in serenity 0.12, so it can't be chained anymore.
+
+use super::*;
+
+fn is_standard_framework(type_: Type<'_>) -> bool {
+    let Some(adt) = type_.adt() else { return false };
+    matches!(&*adt.path().parts(), [crate_, framework, standard, .., name] if crate_ == "serenity" && framework == "framework" && standard == "standard" && name == "StandardFramework")
+}
+
+/// `let framework = StandardFramework::new().configure(|c| c.prefix("~")).group(&GENERAL_GROUP);`
+/// -> `let framework = StandardFramework::new().group(&GENERAL_GROUP);
+/// framework.configure(Configuration::new().prefix("~"));`. If the framework isn't bound to a
+/// variable, like in `.framework(StandardFramework::new().configure(|c| ..))`, it's bound to
+/// `framework`, or `standard_framework` if that name is taken, before the statement using it.
+/// Returns the replacement for each changed part of the code.
+///
+/// Returns None if `expr` is not a call to `StandardFramework::configure`.
+pub fn migrate_framework_configure(
+    expr: Expr<'_>,
+) -> Option<Result<Vec<(rustc_span::Span, String)>, Unsupported>> {
+    let call = expr.method_call()?;
+    if call.method_name() != "configure" || !call.self_type().map_or(false, is_standard_framework) {
+        return None;
+    }
+    let arg = single_arg(&call).ok()?;
+    let configuration = match migrate(arg) {
+        Some(Ok(configuration)) => configuration,
+        Some(Err(unsupported)) => return Some(Err(unsupported)),
+        None => {
+            return Some(Err(Unsupported::new(arg.span(), "expected a configuration closure")));
+        }
+    };
+
+    // The rest of the method call chain, like `.group(&GENERAL_GROUP)`, is kept
+    let mut chain_end = expr;
+    while let Some(parent) = chain_end.parent() {
+        if parent.method_call().map_or(true, |call| call.receiver() != chain_end) {
+            break;
+        }
+        chain_end = parent;
+    }
+    let configure_span = expr.source_span().with_lo(call.receiver().source_span().hi());
+
+    let Some((item_span, indent)) = chain_end.enclosing_block_item() else {
+        return Some(Err(Unsupported::new(
+            call.method_name_span(),
+            "expected the framework to be configured in a block",
+        )));
+    };
+    let bound_pats = chain_end.bound_pats();
+    if let [pat] = &*bound_pats {
+        if let Some(name) = pat.binding_name() {
+            return Some(Ok(vec![
+                (configure_span, String::new()),
+                (item_span.shrink_to_hi(), format!("\n{indent}{name}.configure({configuration});")),
+            ]));
+        }
+    }
+
+    // The binding mustn't shadow anything which the function refers to
+    let Some(name) = ["framework", "standard_framework"]
+        .into_iter()
+        .find(|name| !chain_end.body_refers_to_name(name))
+    else {
+        return Some(Err(Unsupported::new(
+            call.method_name_span(),
+            "expected the framework to be bound to a variable, because `framework` and \
+             `standard_framework` are used in this function already",
+        )));
+    };
+    let framework = chain_end.source_code_with_replacements(vec![(configure_span, String::new())]);
+    let declaration =
+        format!("let {name} = {framework};\n{indent}{name}.configure({configuration});\n{indent}");
+    // Like a trailing expression, which the declaration can't be inserted before separately
+    if item_span.lo() == chain_end.source_span().lo() {
+        return Some(Ok(vec![(chain_end.source_span(), format!("{declaration}{name}"))]));
+    }
+    Some(Ok(vec![
+        (item_span.shrink_to_lo(), declaration),
+        (chain_end.source_span(), name.to_owned()),
+    ]))
+}
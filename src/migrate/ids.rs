@@ -0,0 +1,40 @@
+//! Migrates serenity IDs, which wrap a `NonZeroU64` instead of a `u64` in 0.12
+
+use super::*;
+
+/// Whether the type is one of the ID types in `serenity::model::id`, or a reference to one
+fn is_id(type_: Type<'_>) -> bool {
+    let Some(adt) = type_.peel_refs().adt() else { return false };
+    matches!(&*adt.path().parts(), [crate_, model, id, _] if crate_ == "serenity" && model == "model" && id == "id")
+}
+
+/// `UserId(123)` -> `UserId::new(123)`, `user_id.0` -> `user_id.get()` and `*user_id.as_u64()` ->
+/// `user_id.get()`. Returns the replacement for each changed part of the expression.
+///
+/// Returns None if `expr` is none of these.
+pub fn migrate_id(expr: Expr<'_>) -> Option<Vec<(rustc_span::Span, String)>> {
+    if expr.span().from_expansion() {
+        return None;
+    }
+
+    if let Some(call) = expr.call() {
+        // The tuple struct constructor is the only function with the same path as the ID type
+        let func = call.func();
+        if !is_id(expr.type_()) || func.path()?.parts() != expr.type_().adt()?.path().parts() {
+            return None;
+        }
+        Some(vec![(func.source_span(), format!("{}::new", func.source_code()))])
+    } else if let Some(field) = expr.field() {
+        if field.name() != "0" || !is_id(field.base().type_()) {
+            return None;
+        }
+        Some(vec![(field.name_span(), "get()".to_owned())])
+    } else {
+        let call = expr.deref()?.method_call()?;
+        if call.method_name() != "as_u64" || !is_id(call.receiver().type_()) {
+            return None;
+        }
+        let deref_operator = expr.span().until(call.receiver().span());
+        Some(vec![(deref_operator, String::new()), (call.method_name_span(), "get".to_owned())])
+    }
+}
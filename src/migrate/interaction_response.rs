@@ -0,0 +1,65 @@
+//! Migrates `CreateInteractionResponse` closures, which became an enum in serenity 0.12
+
+use super::*;
+
+/// `|r| r.kind(InteractionResponseType::X).interaction_response_data(|d| ..)` ->
+/// `CreateInteractionResponse::X(..)`
+pub(super) fn migrate(closure: &BuilderClosure<'_>) -> Result<String, Unsupported> {
+    // This was the default in 0.11
+    let mut kind = "ChannelMessageWithSource".to_owned();
+    let mut data = None;
+    for call in flat_call_chain(closure)? {
+        match &*call.method_name() {
+            "kind" => {
+                kind = call
+                    .args()
+                    .next()
+                    .and_then(|arg| arg.path())
+                    .and_then(|path| path.parts().pop())
+                    .ok_or_else(|| {
+                        Unsupported::new(
+                            call.span(),
+                            "expected an `InteractionResponseType` variant",
+                        )
+                    })?;
+            }
+            "interaction_response_data" => data = call.args().next(),
+            other => {
+                return Err(Unsupported::new(call.span(), format!("unknown method `{other}`")));
+            }
+        }
+    }
+
+    // Variants are named like serenity 0.12 CreateInteractionResponse variants
+    let (variant, inner_builder) = match &*kind {
+        "Pong" => ("Pong", None),
+        "ChannelMessageWithSource" => ("Message", Some("CreateInteractionResponseMessage")),
+        "DeferredChannelMessageWithSource" => ("Defer", Some("CreateInteractionResponseMessage")),
+        "DeferredUpdateMessage" => ("Acknowledge", None),
+        "UpdateMessage" => ("UpdateMessage", Some("CreateInteractionResponseMessage")),
+        "Autocomplete" => ("Autocomplete", Some("CreateAutocompleteResponse")),
+        "Modal" => ("Modal", Some("CreateModal")),
+        other => {
+            let message = format!("unknown interaction response type `{other}`");
+            return Err(Unsupported::new(closure.expr.span(), message));
+        }
+    };
+    let Some(inner_builder) = inner_builder else {
+        return Ok(format!("CreateInteractionResponse::{variant}"));
+    };
+
+    let constructor = Constructor::new(inner_builder);
+    let inner = match data {
+        Some(data) => {
+            let data_closure = BuilderClosure::new(data).ok_or_else(|| {
+                Unsupported::new(data.span(), "expected a closure for the response data")
+            })?;
+            migrate_closure(&data_closure, &constructor)?
+        }
+        None => {
+            let required_args = take_required_args(closure.expr, &constructor, &mut [], &[], &[])?;
+            format!("{}({required_args})", constructor.path)
+        }
+    };
+    Ok(format!("CreateInteractionResponse::{variant}({inner})"))
+}
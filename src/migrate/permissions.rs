@@ -0,0 +1,62 @@
+//! Migrates `Permissions` constants which were renamed in serenity 0.12
+
+use super::*;
+
+/// Permissions which were renamed in serenity 0.12: old name, new name
+static RENAMED_PERMISSIONS: &[(&str, &str)] = &[
+    ("MANAGE_EMOJIS_AND_STICKERS", "MANAGE_GUILD_EXPRESSIONS"),
+    ("USE_SLASH_COMMANDS", "USE_APPLICATION_COMMANDS"),
+];
+
+fn renamed_permission(name: &str) -> Option<&'static str> {
+    RENAMED_PERMISSIONS
+        .iter()
+        .find(|(old_name, _)| *old_name == name)
+        .map(|(_, new_name)| *new_name)
+}
+
+/// `Permissions::USE_SLASH_COMMANDS` -> `Permissions::USE_APPLICATION_COMMANDS`. Returns the span
+/// of the constant's name and its new name.
+///
+/// Returns None if `expr` is not a renamed `Permissions` constant.
+pub fn migrate_permission(expr: Expr<'_>) -> Option<(rustc_span::Span, String)> {
+    let [crate_, .., type_name] = &*expr.impl_self_type()?.adt()?.path().parts() else {
+        return None;
+    };
+    if !(crate_ == "serenity" && type_name == "Permissions") {
+        return None;
+    }
+    let new_name = renamed_permission(expr.path()?.parts().last()?)?;
+
+    let name_span = expr.path_name_span()?;
+    if name_span.from_expansion() {
+        return None;
+    }
+    Some((name_span, new_name.to_owned()))
+}
+
+/// `#[required_permissions(USE_SLASH_COMMANDS)]` -> `#[required_permissions(USE_APPLICATION_COMMANDS)]`
+/// on standard framework commands and groups. Returns the span of each renamed permission and its
+/// new name.
+///
+/// `options` is the `static` generated by `#[command]` or `#[group]`, which consume the attribute.
+pub fn migrate_required_permissions(options: Static<'_>) -> Vec<(rustc_span::Span, String)> {
+    let Some(adt) = options.type_().adt() else { return Vec::new() };
+    let is_options = matches!(
+        &*adt.path().parts(),
+        [crate_, framework, .., name]
+            if crate_ == "serenity" && framework == "framework"
+                && (name == "CommandOptions" || name == "GroupOptions")
+    );
+    if !is_options {
+        return Vec::new();
+    }
+
+    options
+        .macro_helper_attributes()
+        .iter()
+        .filter(|attribute| attribute.name() == "required_permissions")
+        .flat_map(|attribute| attribute.argument_idents())
+        .filter_map(|(name, span)| Some((span, renamed_permission(&name)?.to_owned())))
+        .collect()
+}
@@ -0,0 +1,163 @@
+//! Migrates paths to serenity types and enum variants which were renamed in 0.12
+
+use super::*;
+
+/// Types and enum variants which were renamed in serenity 0.12: last parts of the old path, new
+/// name, path of the module or enum which contains the item in 0.12
+static RENAMED_PATHS: &[(&[&str], &str, &str)] = &[
+    (&["ApplicationCommandInteraction"], "CommandInteraction", "serenity::model::application"),
+    (&["MessageComponentInteraction"], "ComponentInteraction", "serenity::model::application"),
+    (&["ModalSubmitInteraction"], "ModalInteraction", "serenity::model::application"),
+    (&["AutocompleteInteraction"], "CommandInteraction", "serenity::model::application"),
+    (
+        &["Interaction", "ApplicationCommand"],
+        "Command",
+        "serenity::model::application::Interaction",
+    ),
+    (
+        &["Interaction", "MessageComponent"],
+        "Component",
+        "serenity::model::application::Interaction",
+    ),
+    (&["Interaction", "ModalSubmit"], "Modal", "serenity::model::application::Interaction"),
+    (
+        &["InteractionType", "ApplicationCommand"],
+        "Command",
+        "serenity::model::application::InteractionType",
+    ),
+    (
+        &["InteractionType", "MessageComponent"],
+        "Component",
+        "serenity::model::application::InteractionType",
+    ),
+    (&["InteractionType", "ModalSubmit"], "Modal", "serenity::model::application::InteractionType"),
+    (&["MessageCollectorBuilder"], "MessageCollector", "serenity::collector"),
+    (&["CollectReply"], "MessageCollector", "serenity::collector"),
+    (&["ReactionCollectorBuilder"], "ReactionCollector", "serenity::collector"),
+    (&["CollectReaction"], "ReactionCollector", "serenity::collector"),
+    (
+        &["ComponentInteractionCollectorBuilder"],
+        "ComponentInteractionCollector",
+        "serenity::collector",
+    ),
+    (&["CollectComponentInteraction"], "ComponentInteractionCollector", "serenity::collector"),
+    (&["ModalInteractionCollectorBuilder"], "ModalInteractionCollector", "serenity::collector"),
+    (&["CollectModalInteraction"], "ModalInteractionCollector", "serenity::collector"),
+    (&["Activity"], "ActivityData", "serenity::gateway"),
+];
+
+/// The old path, new name and module of a renamed serenity item
+fn renamed_item(
+    path: Path<'_>,
+) -> Option<&'static (&'static [&'static str], &'static str, &'static str)> {
+    let parts = path.parts();
+    if parts.first()? != "serenity" {
+        return None;
+    }
+    RENAMED_PATHS.iter().find(|(old_path, _, _)| {
+        parts.iter().rev().zip(old_path.iter().rev()).all(|(part, old_part)| part == old_part)
+    })
+}
+
+/// Renames the last segment of a path to a renamed serenity item, like `ApplicationCommand` in
+/// `Interaction::ApplicationCommand`. Paths which include the module of the item, like
+/// `gateway::Activity`, are replaced by the item's path in serenity 0.12, because the module may
+/// have changed as well. Returns the span of the replaced part of the path and its replacement.
+///
+/// Returns None if the path doesn't point to a renamed item.
+fn migrate_path(
+    path: Option<Path<'_>>,
+    name_span: Option<rustc_span::Span>,
+    written_path_span: Option<(rustc_span::Span, usize)>,
+) -> Option<(rustc_span::Span, String)> {
+    let &(old_path, new_name, new_module) = renamed_item(path?)?;
+
+    // The path may have been generated by a macro, in which case there's nothing to replace
+    let name_span = name_span?;
+    if name_span.from_expansion() {
+        return None;
+    }
+    match written_path_span {
+        Some((span, segments)) if segments > old_path.len() && !span.from_expansion() => {
+            Some((span, format!("{new_module}::{new_name}")))
+        }
+        _ => Some((name_span, new_name.to_owned())),
+    }
+}
+
+/// `Interaction::ApplicationCommand(command)` -> `Interaction::Command(command)`
+pub fn migrate_expr_path(expr: Expr<'_>) -> Option<(rustc_span::Span, String)> {
+    migrate_path(expr.path(), expr.path_name_span(), expr.written_path_span())
+}
+
+/// `Interaction::ApplicationCommand(command) => ..` -> `Interaction::Command(command) => ..`
+pub fn migrate_pat_path(pat: Pat<'_>) -> Option<(rustc_span::Span, String)> {
+    migrate_path(pat.path(), pat.path_name_span(), pat.written_path_span())
+}
+
+/// `command: &ApplicationCommandInteraction` -> `command: &CommandInteraction`
+pub fn migrate_type_path(type_: HirType<'_>) -> Option<(rustc_span::Span, String)> {
+    migrate_path(type_.path(), type_.path_name_span(), type_.written_path_span())
+}
+
+/// A migrated import of a renamed item
+pub struct UseMigration {
+    pub span: rustc_span::Span,
+    pub replacement: String,
+    /// Why the migration may be incorrect, if it may be
+    pub caveat: Option<String>,
+}
+
+/// The name an import is bound to after the migration
+fn migrated_name(use_: Use<'_>) -> String {
+    let renamed = use_.path().and_then(renamed_item);
+    match renamed {
+        Some(&(_, new_name, _)) if use_.path_name().as_deref() == Some(&*use_.name()) => {
+            new_name.to_owned()
+        }
+        _ => use_.name(),
+    }
+}
+
+/// `use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;`
+/// -> `use serenity::model::application::CommandInteraction;`. The module of the item may have
+/// changed as well, so the whole path is replaced. In nested imports like
+/// `use serenity::collector::{CollectReply, ..};`, only the name is replaced. Imports which would
+/// import the same name twice, like `AutocompleteInteraction` next to
+/// `ApplicationCommandInteraction`, are removed.
+///
+/// Returns None if `use_` doesn't import a renamed item.
+pub fn migrate_use_path(use_: Use<'_>) -> Option<Result<UseMigration, Unsupported>> {
+    let &(_, new_name, new_module) = renamed_item(use_.path()?)?;
+    let path_span = use_.path_span();
+    if path_span.from_expansion() {
+        return None;
+    }
+
+    let name = migrated_name(use_);
+    // Of imports with the same name, the first renamed one is kept
+    let is_duplicate = use_.sibling_uses().into_iter().any(|other| {
+        let is_kept =
+            other.span().lo() < use_.span().lo() || other.path().and_then(renamed_item).is_none();
+        is_kept && migrated_name(other) == name
+    });
+    match (is_duplicate, use_.is_nested()) {
+        (true, true) => Some(Err(Unsupported::new(
+            use_.span(),
+            format!("`{name}` is imported already, remove this import"),
+        ))),
+        (true, false) => {
+            Some(Ok(UseMigration { span: use_.span(), replacement: String::new(), caveat: None }))
+        }
+        (false, true) => Some(Ok(UseMigration {
+            span: use_.path_name_span()?,
+            replacement: new_name.to_owned(),
+            caveat: Some(format!("`{new_name}` is in `{new_module}` in serenity 0.12")),
+        })),
+        (false, false) => Some(Ok(UseMigration {
+            span: path_span,
+            replacement: format!("{new_module}::{new_name}"),
+            caveat: None,
+        })),
+    }
+}
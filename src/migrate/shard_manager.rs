@@ -0,0 +1,47 @@
+//! Migrates uses of `ShardManager`, which isn't wrapped in a `Mutex` anymore in serenity 0.12
+
+use super::*;
+
+fn is_shard_manager(path: Path<'_>) -> bool {
+    matches!(&*path.parts(), [crate_, .., name] if crate_ == "serenity" && name == "ShardManager")
+}
+
+fn is_mutex(path: Path<'_>) -> bool {
+    matches!(&*path.parts(), [crate_, .., name] if crate_ == "tokio" && name == "Mutex")
+}
+
+/// `shard_manager.lock().await.shards_instantiated()` -> `shard_manager.shards_instantiated()`,
+/// whatever the type of `shard_manager` is, as long as it dereferences to a
+/// `Mutex<ShardManager>`. Returns the span of `.lock().await`, which is removed.
+///
+/// Returns None if `expr` doesn't lock a shard manager.
+pub fn migrate_shard_manager_lock(expr: Expr<'_>) -> Option<rustc_span::Span> {
+    let call = expr.method_call()?;
+    if call.method_name() != "lock" {
+        return None;
+    }
+    let mutex = call.receiver().adjusted_type().peel_refs();
+    if !is_mutex(mutex.adt()?.path())
+        || !is_shard_manager(mutex.generic_args().pop()?.adt()?.path())
+    {
+        return None;
+    }
+
+    let await_span = expr.await_span()?;
+    Some(await_span.with_lo(call.receiver().source_span().hi()))
+}
+
+/// `Arc<Mutex<ShardManager>>` -> `Arc<ShardManager>`, for example in `TypeMapKey` impls. Returns
+/// the span of `Mutex<ShardManager>` and its replacement.
+///
+/// Returns None if `type_` is not a `Mutex<ShardManager>`.
+pub fn migrate_shard_manager_type(type_: HirType<'_>) -> Option<(rustc_span::Span, String)> {
+    if !is_mutex(type_.path()?) {
+        return None;
+    }
+    let [shard_manager] = &*type_.generic_args() else { return None };
+    if !is_shard_manager(shard_manager.path()?) {
+        return None;
+    }
+    Some((type_.source_span(), shard_manager.source_code()))
+}
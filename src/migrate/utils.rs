@@ -0,0 +1,73 @@
+//! Migrates uses of `serenity::utils` functions which were renamed in 0.12
+
+use super::*;
+
+/// Functions which were renamed in serenity 0.12: full path, new name
+static RENAMED_UTILS: &[(&[&str], &str)] = &[
+    (&["serenity", "utils", "parse_username"], "parse_user_mention"),
+    (&["serenity", "utils", "parse_channel"], "parse_channel_mention"),
+    (&["serenity", "utils", "parse_role"], "parse_role_mention"),
+];
+
+/// The old and new name of the renamed function which `path` resolves to
+fn renamed_util(path: Path<'_>) -> Option<(String, &'static str)> {
+    let parts = path.parts();
+    let &(_, new_name) = RENAMED_UTILS.iter().find(|(old_path, _)| parts == *old_path)?;
+    Some((parts.last()?.clone(), new_name))
+}
+
+/// Renames a path to a function. Returns None if the function was imported with
+/// `use .. as ..`, in which case the `use` item is renamed instead.
+fn rename(
+    (old_name, new_name): (String, &str),
+    name: String,
+    name_span: rustc_span::Span,
+) -> Option<(rustc_span::Span, String)> {
+    if name != old_name || name_span.from_expansion() {
+        return None;
+    }
+    Some((name_span, new_name.to_owned()))
+}
+
+/// `parse_username(&args[0])` -> `parse_user_mention(&args[0])`, and `parse_username(name)` ->
+/// `parse_user_mention(&name)` because the new functions take a `&str` instead of
+/// `impl AsRef<str>`. Paths to the functions outside of calls, like in
+/// `args.iter().map(parse_role)`, are renamed too. Returns the replacement for each changed part
+/// of the expression.
+///
+/// Returns None if `expr` is not a call or path to a renamed function.
+pub fn migrate_utils_fn(expr: Expr<'_>) -> Option<Vec<(rustc_span::Span, String)>> {
+    let Some(call) = expr.call() else {
+        // Calls are migrated as a whole
+        if expr.parent().and_then(|parent| parent.call()).map_or(false, |call| call.func() == expr) {
+            return None;
+        }
+        let renamed = renamed_util(expr.path()?)?;
+        return Some(vec![rename(renamed, expr.path_name()?, expr.path_name_span()?)?]);
+    };
+
+    let func = call.func();
+    let renamed = renamed_util(func.path()?)?;
+    let mut replacements = Vec::new();
+    replacements.extend(rename(renamed, func.path_name()?, func.path_name_span()?));
+    for arg in call.args() {
+        if arg.type_().ref_().is_none() {
+            replacements.push((arg.source_span().shrink_to_lo(), "&".to_owned()));
+        }
+    }
+    (!replacements.is_empty()).then_some(replacements)
+}
+
+/// `use serenity::utils::parse_username as parse;` ->
+/// `use serenity::utils::parse_user_mention as parse;`. Returns the span of the imported name and
+/// its new name.
+///
+/// Returns None if `use_` doesn't import a renamed function.
+pub fn migrate_utils_use(use_: Use<'_>) -> Option<(rustc_span::Span, String)> {
+    let (_, new_name) = renamed_util(use_.path()?)?;
+    let name_span = use_.path_name_span()?;
+    if name_span.from_expansion() {
+        return None;
+    }
+    Some((name_span, new_name.to_owned()))
+}
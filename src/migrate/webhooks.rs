@@ -0,0 +1,131 @@
+//! Migrates webhook calls. `ExecuteWebhook` and `EditWebhookMessage` closures are migrated like
+//! any other builder, but their embeds were JSON values built with `Embed::fake`, which was
+//! removed in serenity 0.12. Creating a webhook takes a `CreateWebhook` builder now.
+
+use super::*;
+
+/// `Embed::fake(|e| e.title("x"))` -> `CreateEmbed::new().title("x")`
+///
+/// Returns None if `expr` is not a call to `Embed::fake`.
+pub fn migrate_fake_embed(expr: Expr<'_>) -> Option<Result<String, Unsupported>> {
+    let call = expr.call()?;
+    let func = call.func();
+    let [crate_, .., type_name] = &*func.impl_self_type()?.adt()?.path().parts() else {
+        return None;
+    };
+    if !(crate_ == "serenity" && type_name == "Embed" && func.path()?.parts().last()? == "fake") {
+        return None;
+    }
+
+    let [closure] = &*call.args().collect::<Vec<_>>() else { return None };
+    Some(migrate(*closure).unwrap_or_else(|| {
+        Err(Unsupported::new(
+            closure.span(),
+            "`Embed::fake` has been removed in serenity 0.12, webhooks take a `CreateEmbed` \
+             instead",
+        ))
+    }))
+}
+
+/// The source code of `expr` with each `Embed::fake` call in it migrated, like in
+/// `.embeds(vec![Embed::fake(|e| ..)])`
+pub fn migrate_nested_fake_embeds(expr: Expr<'_>) -> Result<String, Unsupported> {
+    let mut replacements = Vec::new();
+    for subexpr in expr.subexprs() {
+        if let Some(migration) = migrate_fake_embed(subexpr) {
+            replacements.push((subexpr.source_span(), migration?));
+        }
+    }
+    Ok(expr.source_code_with_replacements(replacements))
+}
+
+/// Migrates the avatar of `create_webhook_with_avatar`, which took anything converting into an
+/// `AttachmentType`, like a path, to a `CreateAttachment`
+fn migrate_avatar(avatar: Expr<'_>) -> Result<AttachmentMigration, Unsupported> {
+    if let Some(migration) = migrate_attachment(avatar) {
+        return migration;
+    }
+
+    let type_ = avatar.type_();
+    let is_type = |type_: Option<Type<'_>>, path: &[&str]| {
+        type_.and_then(|type_| type_.adt()).map_or(false, |adt| adt.path().parts() == path)
+    };
+    let is_attachment_type = type_.adt().map_or(false, |adt| {
+        matches!(&*adt.path().parts(), [crate_, .., name] if crate_ == "serenity" && name == "AttachmentType")
+    });
+    // Like a variable, whose `AttachmentType` is migrated where it's constructed
+    if is_attachment_type {
+        return Ok(AttachmentMigration {
+            replacement: avatar.source_code(),
+            fallback_reason: None,
+        });
+    }
+    let is_path = type_.ref_().map_or(false, |type_| type_.is_str())
+        || is_type(type_.ref_(), &["std", "path", "Path"])
+        || is_type(Some(type_), &["std", "path", "PathBuf"]);
+    if !is_path {
+        return Err(Unsupported::new(
+            avatar.span(),
+            "expected an `AttachmentType` or a file path as the avatar",
+        ));
+    }
+    let constructor = format!("CreateAttachment::path({})", avatar.source_code());
+    attachment::await_constructor(avatar, constructor)
+}
+
+/// A migrated call creating a webhook
+pub struct CreateWebhookMigration {
+    pub replacements: Vec<(rustc_span::Span, String)>,
+    /// Why the error of reading the avatar isn't propagated with `?`, if it isn't
+    pub fallback_reason: Option<&'static str>,
+}
+
+/// `channel_id.create_webhook(&http, name)` ->
+/// `channel_id.create_webhook(&http, CreateWebhook::new(name))`, and
+/// `channel_id.create_webhook_with_avatar(&http, name, avatar)` ->
+/// `channel_id.create_webhook(&http, CreateWebhook::new(name).avatar(&avatar))`. Avatars given
+/// as a path are read with `CreateAttachment::path`.
+///
+/// Returns None if `expr` is not a call to one of these methods.
+pub fn migrate_create_webhook(
+    expr: Expr<'_>,
+) -> Option<Result<CreateWebhookMigration, Unsupported>> {
+    let call = expr.method_call()?;
+    let [crate_, .., type_name] = &*call.self_type()?.adt()?.path().parts() else { return None };
+    if !(crate_ == "serenity" && (type_name == "ChannelId" || type_name == "GuildChannel")) {
+        return None;
+    }
+
+    let args = call.args().collect::<Vec<_>>();
+    match (&*call.method_name(), &*args) {
+        ("create_webhook", [_, name]) => Some(Ok(CreateWebhookMigration {
+            replacements: vec![(
+                name.source_span(),
+                format!("CreateWebhook::new({})", name.source_code()),
+            )],
+            fallback_reason: None,
+        })),
+        ("create_webhook_with_avatar", [_, name, avatar_arg]) => {
+            let avatar = match migrate_avatar(*avatar_arg) {
+                Ok(avatar) => avatar,
+                Err(unsupported) => return Some(Err(unsupported)),
+            };
+            let replacements = vec![
+                (call.method_name_span(), "create_webhook".to_owned()),
+                (
+                    name.source_span().to(avatar_arg.source_span()),
+                    format!(
+                        "CreateWebhook::new({}).avatar(&{})",
+                        name.source_code(),
+                        avatar.replacement
+                    ),
+                ),
+            ];
+            Some(Ok(CreateWebhookMigration {
+                replacements,
+                fallback_reason: avatar.fallback_reason,
+            }))
+        }
+        _ => None,
+    }
+}
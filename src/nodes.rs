@@ -7,24 +7,67 @@ pub trait Node<'hir> {
     fn cx(&self) -> Context<'hir>;
     fn span(&self) -> rustc_span::Span;
 
+    /// Span of the code as written by the user, i.e. with macro expansions like `vec![..]` walked
+    /// back to their call site
+    fn source_span(&self) -> rustc_span::Span {
+        rustc_span::hygiene::walk_chain(self.span(), rustc_span::SyntaxContext::root())
+    }
+
     fn source_code(&self) -> String {
         self.cx()
             .tcx
             .sess
             .source_map()
-            .span_to_snippet(rustc_span::hygiene::walk_chain(self.span(), self.span().ctxt()))
+            .span_to_snippet(self.source_span())
             .unwrap_or_else(|_| "todo!()".to_owned())
     }
+
+    /// Like [`Self::source_code`], but with the given spans within this node replaced. Overlapping
+    /// replacements are skipped.
+    fn source_code_with_replacements(
+        &self,
+        mut replacements: Vec<(rustc_span::Span, String)>,
+    ) -> String {
+        let span = self.source_span();
+        let snippet = |lo, hi| {
+            self.cx()
+                .tcx
+                .sess
+                .source_map()
+                .span_to_snippet(span.with_lo(lo).with_hi(hi))
+                .unwrap_or_else(|_| "todo!()".to_owned())
+        };
+
+        replacements.sort_by_key(|(span, _)| (span.lo(), span.hi()));
+        let mut output = String::new();
+        let mut cursor = span.lo();
+        for (replaced_span, replacement) in replacements {
+            let replaced_span = rustc_span::hygiene::walk_chain(replaced_span, span.ctxt());
+            if replaced_span.lo() < cursor || replaced_span.hi() > span.hi() {
+                continue;
+            }
+            output += &snippet(cursor, replaced_span.lo());
+            output += &replacement;
+            cursor = replaced_span.hi();
+        }
+        output += &snippet(cursor, span.hi());
+        output
+    }
 }
 
 #[derive(Copy, Clone)]
 pub struct Context<'hir> {
     tcx: rustc_middle::ty::TyCtxt<'hir>,
-    typeck_results: &'hir rustc_middle::ty::TypeckResults<'hir>,
+    /// None outside of function bodies, like in struct definitions
+    typeck_results: Option<&'hir rustc_middle::ty::TypeckResults<'hir>>,
 }
 impl<'hir> Context<'hir> {
+    fn new(cx: &rustc_lint::LateContext<'hir>) -> Self {
+        Self { tcx: cx.tcx, typeck_results: cx.maybe_typeck_results() }
+    }
+
     fn typeck_results(&self) -> &'hir rustc_middle::ty::TypeckResults<'hir> {
-        self.typeck_results
+        self.typeck_results.expect("type information is only available inside function bodies")
     }
 }
 impl std::fmt::Debug for Context<'_> {
@@ -33,6 +76,38 @@ impl std::fmt::Debug for Context<'_> {
     }
 }
 
+/// The self type of the impl block which defines the given associated item
+fn impl_self_type(cx: Context<'_>, def_id: rustc_span::def_id::DefId) -> Option<Type<'_>> {
+    let impl_id = cx.tcx.impl_of_method(def_id)?;
+    Some(Type { cx, inner: cx.tcx.type_of(impl_id).subst_identity() })
+}
+
+/// The definition a path like `a::b` or `A::b` resolves to
+fn qpath_def_path<'hir>(
+    cx: Context<'hir>,
+    qpath: &rustc_hir::QPath<'_>,
+    hir_id: rustc_hir::HirId,
+) -> Option<Path<'hir>> {
+    let def_id = cx.typeck_results().qpath_res(qpath, hir_id).opt_def_id()?;
+    Some(Path { cx, inner: cx.tcx.def_path(def_id) })
+}
+
+/// Span of the last segment of a path, like `b` in `a::b`
+fn qpath_name_span(qpath: &rustc_hir::QPath<'_>) -> Option<rustc_span::Span> {
+    match qpath {
+        rustc_hir::QPath::Resolved(_, path) => Some(path.segments.last()?.ident.span),
+        rustc_hir::QPath::TypeRelative(_, segment) => Some(segment.ident.span),
+        rustc_hir::QPath::LangItem(..) => None,
+    }
+}
+
+/// Span of a resolved path as written, like `gateway::Activity`, and its number of segments
+fn qpath_written_span(qpath: &rustc_hir::QPath<'_>) -> Option<(rustc_span::Span, usize)> {
+    let rustc_hir::QPath::Resolved(None, path) = qpath else { return None };
+    let name = path.segments.last()?.ident.span;
+    Some((path.span.with_hi(name.hi()), path.segments.len()))
+}
+
 pub struct Path<'hir> {
     cx: Context<'hir>,
     inner: rustc_hir::definitions::DefPath,
@@ -68,20 +143,281 @@ pub struct Type<'hir> {
     cx: Context<'hir>,
     inner: rustc_middle::ty::Ty<'hir>,
 }
+impl PartialEq for Type<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
 impl<'hir> Type<'hir> {
     pub fn ref_(&self) -> Option<Self> {
         match self.inner.kind() {
-            rustc_middle::ty::TyKind::Ref(_, inner, _) => {
-                Some(Self { cx: self.cx, inner: inner.clone() })
-            }
+            rustc_middle::ty::TyKind::Ref(_, inner, _) => Some(Self { cx: self.cx, inner: *inner }),
             _ => None,
         }
     }
 
+    /// The type behind any number of references, like `UserId` in `&&UserId`
+    pub fn peel_refs(&self) -> Self {
+        Self { cx: self.cx, inner: self.inner.peel_refs() }
+    }
+
     pub fn adt(&self) -> Option<Adt<'hir>> {
         let rustc_middle::ty::TyKind::Adt(adt, _) = self.inner.kind() else { return None };
         Some(Adt { cx: self.cx, inner: adt })
     }
+
+    pub fn is_str(&self) -> bool {
+        matches!(self.inner.kind(), rustc_middle::ty::TyKind::Str)
+    }
+
+    /// The principal trait of a trait object, like `Error` in `dyn Error + Send + Sync`
+    pub fn dyn_trait(&self) -> Option<Path<'hir>> {
+        let rustc_middle::ty::TyKind::Dynamic(predicates, ..) = self.inner.kind() else {
+            return None;
+        };
+        Some(Path { cx: self.cx, inner: self.cx.tcx.def_path(predicates.principal_def_id()?) })
+    }
+
+    /// Generic type arguments of an ADT, like `T` and `E` in `Result<T, E>`
+    pub fn generic_args(&self) -> Vec<Self> {
+        let rustc_middle::ty::TyKind::Adt(_, substs) = self.inner.kind() else { return Vec::new() };
+        substs.types().map(|inner| Self { cx: self.cx, inner }).collect()
+    }
+}
+/// A type as written in the source code, like `Arc<Mutex<ShardManager>>`. See [`Type`] for the
+/// type of an expression as inferred by the compiler.
+#[derive(Debug, Clone, Copy)]
+pub struct HirType<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Ty<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for HirType<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> HirType<'hir> {
+    pub fn new(cx: &rustc_lint::LateContext<'hir>, inner: &'hir rustc_hir::Ty<'hir>) -> Self {
+        Self { cx: Context::new(cx), inner }
+    }
+
+    /// The definition a path type like `ApplicationCommandInteraction` resolves to. Generic
+    /// arguments aren't included, so `Arc<ShardManager>` resolves to `Arc`. Type-relative paths
+    /// like `Self::Value` aren't resolved.
+    pub fn path(&self) -> Option<Path<'hir>> {
+        let rustc_hir::TyKind::Path(rustc_hir::QPath::Resolved(_, path)) = &self.inner.kind else {
+            return None;
+        };
+        Some(Path { cx: self.cx, inner: self.cx.tcx.def_path(path.res.opt_def_id()?) })
+    }
+
+    /// Generic type arguments of a path type, like `ShardManager` in `Mutex<ShardManager>`
+    pub fn generic_args(&self) -> Vec<HirType<'hir>> {
+        let rustc_hir::TyKind::Path(rustc_hir::QPath::Resolved(_, path)) = &self.inner.kind else {
+            return Vec::new();
+        };
+        let Some(args) = path.segments.last().and_then(|segment| segment.args) else {
+            return Vec::new();
+        };
+        args.args
+            .iter()
+            .filter_map(|arg| match arg {
+                rustc_hir::GenericArg::Type(inner) => Some(HirType { cx: self.cx, inner }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Span of the last segment of a path type, like `ShardManager` in `bridge::gateway::ShardManager`
+    pub fn path_name_span(&self) -> Option<rustc_span::Span> {
+        let rustc_hir::TyKind::Path(qpath) = &self.inner.kind else { return None };
+        qpath_name_span(qpath)
+    }
+
+    /// Span of a path type as written, like `gateway::Activity` without generic arguments, and its
+    /// number of segments. Type-relative paths like `Self::Value` aren't included.
+    pub fn written_path_span(&self) -> Option<(rustc_span::Span, usize)> {
+        let rustc_hir::TyKind::Path(qpath) = &self.inner.kind else { return None };
+        qpath_written_span(qpath)
+    }
+}
+/// A pattern, like `Interaction::ApplicationCommand(command)` in a match arm
+#[derive(Debug, Clone, Copy)]
+pub struct Pat<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Pat<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Pat<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> Pat<'hir> {
+    pub fn new(cx: &rustc_lint::LateContext<'hir>, inner: &'hir rustc_hir::Pat<'hir>) -> Self {
+        Self { cx: Context::new(cx), inner }
+    }
+
+    fn qpath(&self) -> Option<&'hir rustc_hir::QPath<'hir>> {
+        match &self.inner.kind {
+            rustc_hir::PatKind::Path(qpath)
+            | rustc_hir::PatKind::TupleStruct(qpath, ..)
+            | rustc_hir::PatKind::Struct(qpath, ..) => Some(qpath),
+            _ => None,
+        }
+    }
+
+    /// The definition the path in a pattern like `Interaction::Ping(_)` or
+    /// `Channel::Guild { .. }` resolves to
+    pub fn path(&self) -> Option<Path<'hir>> {
+        qpath_def_path(self.cx, self.qpath()?, self.inner.hir_id)
+    }
+
+    /// Span of the last segment of the path in the pattern, like `Ping` in `Interaction::Ping(_)`
+    pub fn path_name_span(&self) -> Option<rustc_span::Span> {
+        qpath_name_span(self.qpath()?)
+    }
+
+    /// Span of the path in the pattern as written, like `interaction::Interaction::Ping` in
+    /// `interaction::Interaction::Ping(_)`, and its number of segments
+    pub fn written_path_span(&self) -> Option<(rustc_span::Span, usize)> {
+        qpath_written_span(self.qpath()?)
+    }
+
+    /// Fields of a tuple struct or tuple variant pattern, like `channel` in
+    /// `Channel::Guild(channel)`
+    pub fn tuple_struct_fields(&self) -> Option<Vec<Pat<'hir>>> {
+        let rustc_hir::PatKind::TupleStruct(_, fields, _) = self.inner.kind else { return None };
+        Some(fields.iter().map(|inner| Pat { cx: self.cx, inner }).collect())
+    }
+
+    /// The name of a binding pattern, like `channel` in `ref channel`
+    pub fn binding_name(&self) -> Option<String> {
+        let rustc_hir::PatKind::Binding(_, _, ident, _) = self.inner.kind else { return None };
+        Some(ident.to_string())
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self.inner.kind, rustc_hir::PatKind::Wild)
+    }
+
+    /// The expression a `let`, `if let` or `match` pattern is matched against, like `x` in
+    /// `if let Some(Channel::Guild(c)) = x`. Also works for patterns nested within.
+    pub fn matched_expr(&self) -> Option<Expr<'hir>> {
+        let hir = self.cx.tcx.hir();
+        for (id, parent) in hir.parent_iter(self.inner.hir_id) {
+            let init = match parent {
+                rustc_hir::Node::Pat(_) => continue,
+                rustc_hir::Node::Local(local) => local.init?,
+                rustc_hir::Node::Expr(rustc_hir::Expr {
+                    kind: rustc_hir::ExprKind::Let(let_),
+                    ..
+                }) => let_.init,
+                rustc_hir::Node::Arm(_) => match hir.get_parent(id) {
+                    rustc_hir::Node::Expr(rustc_hir::Expr {
+                        kind: rustc_hir::ExprKind::Match(scrutinee, ..),
+                        ..
+                    }) => scrutinee,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            return Some(Expr { cx: self.cx, inner: init });
+        }
+        None
+    }
+
+    /// The `if let` or `while let` expression which this is the whole pattern of, like for
+    /// `Channel::Guild(c)` in `if let Channel::Guild(c) = x { .. }`. For `while let` loops, the
+    /// second value is true.
+    pub fn if_let(&self) -> Option<(Expr<'hir>, bool)> {
+        let hir = self.cx.tcx.hir();
+        let let_ = match hir.find_parent(self.inner.hir_id)? {
+            rustc_hir::Node::Expr(
+                let_ @ rustc_hir::Expr { kind: rustc_hir::ExprKind::Let(inner), .. },
+            ) if inner.pat.hir_id == self.inner.hir_id => let_,
+            _ => return None,
+        };
+        let mut parents = hir.parent_iter(let_.hir_id).map(|(_, parent)| parent);
+        let if_ = match parents.next()? {
+            // The condition may be wrapped in `DropTemps`
+            rustc_hir::Node::Expr(rustc_hir::Expr {
+                kind: rustc_hir::ExprKind::DropTemps(_),
+                ..
+            }) => parents.next()?,
+            parent => parent,
+        };
+        let rustc_hir::Node::Expr(if_ @ rustc_hir::Expr { kind: rustc_hir::ExprKind::If(..), .. }) =
+            if_
+        else {
+            return None;
+        };
+        // `while let` loops are desugared to `loop { if let .. { .. } else { break } }`
+        let is_while = matches!(
+            (parents.next(), parents.next()),
+            (
+                Some(rustc_hir::Node::Block(_)),
+                Some(rustc_hir::Node::Expr(rustc_hir::Expr {
+                    kind: rustc_hir::ExprKind::Loop(_, _, rustc_hir::LoopSource::While, _),
+                    ..
+                })),
+            )
+        );
+        Some((Expr { cx: self.cx, inner: if_ }, is_while))
+    }
+
+    /// This pattern and all patterns nested within, outermost first
+    pub fn subpatterns(&self) -> Vec<Pat<'hir>> {
+        struct PatCollector<'hir> {
+            pats: Vec<&'hir rustc_hir::Pat<'hir>>,
+        }
+        impl<'hir> rustc_hir::intravisit::Visitor<'hir> for PatCollector<'hir> {
+            fn visit_pat(&mut self, pat: &'hir rustc_hir::Pat<'hir>) {
+                self.pats.push(pat);
+                rustc_hir::intravisit::walk_pat(self, pat);
+            }
+        }
+
+        let mut collector = PatCollector { pats: Vec::new() };
+        rustc_hir::intravisit::Visitor::visit_pat(&mut collector, self.inner);
+        collector.pats.into_iter().map(|inner| Pat { cx: self.cx, inner }).collect()
+    }
+
+    /// The bindings within this pattern, like `guild` in `Some(guild)`
+    pub fn bindings(&self) -> Vec<Pat<'hir>> {
+        self.subpatterns()
+            .into_iter()
+            .filter(|pat| matches!(pat.inner.kind, rustc_hir::PatKind::Binding(..)))
+            .collect()
+    }
+}
+/// A match arm, like `Channel::Guild(channel) if channel.nsfw => ..`
+#[derive(Debug, Clone, Copy)]
+pub struct Arm<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Arm<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Arm<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> Arm<'hir> {
+    pub fn pat(&self) -> Pat<'hir> {
+        Pat { cx: self.cx, inner: self.inner.pat }
+    }
+
+    /// The condition of an `if` guard. Returns None if there's no guard or if it's an `if let`
+    /// guard.
+    pub fn guard(&self) -> Option<Expr<'hir>> {
+        match self.inner.guard? {
+            rustc_hir::Guard::If(inner) => Some(Expr { cx: self.cx, inner }),
+            rustc_hir::Guard::IfLet(_) => None,
+        }
+    }
+
+    pub fn has_guard(&self) -> bool {
+        self.inner.guard.is_some()
+    }
 }
 pub struct Param<'hir> {
     cx: Context<'hir>,
@@ -94,9 +430,320 @@ impl<'hir> Node<'hir> for Param<'hir> {
     fn span(&self) -> rustc_span::Span { self.inner.span }
 }
 impl<'hir> Param<'hir> {
+    /// Name of the binding, like `e` in `|e| ...`
+    pub fn name(&self) -> Option<String> {
+        let rustc_hir::PatKind::Binding(_, _, ident, _) = self.inner.pat.kind else { return None };
+        Some(ident.to_string())
+    }
+
     pub fn type_(&self) -> Type<'hir> {
         Type { cx: self.cx, inner: self.cx.typeck_results().node_type(self.type_.hir_id) }
     }
+
+    /// The type annotation of this parameter, like `bool` in `is_new: bool`
+    pub fn hir_type(&self) -> HirType<'hir> {
+        HirType { cx: self.cx, inner: self.type_ }
+    }
+}
+/// A function in an impl block, like `async fn ready(&self, ..) { .. }` in
+/// `impl EventHandler for Handler`
+#[derive(Debug, Clone, Copy)]
+pub struct ImplFn<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::ImplItem<'hir>,
+    sig: &'hir rustc_hir::FnSig<'hir>,
+    body: &'hir rustc_hir::Body<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for ImplFn<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> ImplFn<'hir> {
+    /// Returns None if the impl item is not a function
+    pub fn new(
+        cx: &rustc_lint::LateContext<'hir>,
+        inner: &'hir rustc_hir::ImplItem<'hir>,
+    ) -> Option<Self> {
+        let rustc_hir::ImplItemKind::Fn(sig, body_id) = &inner.kind else { return None };
+        let cx = Context { tcx: cx.tcx, typeck_results: Some(cx.tcx.typeck_body(*body_id)) };
+        Some(Self { cx, inner, sig, body: cx.tcx.hir().body(*body_id) })
+    }
+
+    pub fn name(&self) -> String {
+        self.inner.ident.to_string()
+    }
+
+    /// The trait whose method this function implements, like `serenity::client::EventHandler`
+    pub fn trait_path(&self) -> Option<Path<'hir>> {
+        let impl_ = self.cx.tcx.impl_of_method(self.inner.owner_id.to_def_id())?;
+        let trait_ = self.cx.tcx.trait_id_of_impl(impl_)?;
+        Some(Path { cx: self.cx, inner: self.cx.tcx.def_path(trait_) })
+    }
+
+    /// Parameters other than `self`
+    pub fn params(&self) -> Vec<Param<'hir>> {
+        let cx = self.cx;
+        let params = Iterator::zip(self.body.params.iter(), self.sig.decl.inputs.iter())
+            .map(move |(param, type_)| Param { cx, inner: param, type_ });
+        params.skip(usize::from(self.sig.decl.implicit_self.has_implicit_self())).collect()
+    }
+
+    /// The function body as written by the user. Macros like `#[async_trait]` wrap it in
+    /// generated code which has the span of the original body, so this is the innermost of the
+    /// outermost blocks which aren't part of a macro expansion.
+    pub fn body_block(&self) -> Option<Block<'hir>> {
+        struct BlockFinder<'hir> {
+            tcx: rustc_middle::ty::TyCtxt<'hir>,
+            block: Option<&'hir rustc_hir::Block<'hir>>,
+        }
+        impl<'hir> rustc_hir::intravisit::Visitor<'hir> for BlockFinder<'hir> {
+            // The body is usually wrapped in an async block, which is a closure in HIR
+            type NestedFilter = rustc_middle::hir::nested_filter::OnlyBodies;
+            type Map = rustc_middle::hir::map::Map<'hir>;
+
+            fn nested_visit_map(&mut self) -> Self::Map {
+                self.tcx.hir()
+            }
+
+            fn visit_block(&mut self, block: &'hir rustc_hir::Block<'hir>) {
+                match self.block {
+                    None if block.span.from_expansion() => {}
+                    None => self.block = Some(block),
+                    Some(found) if found.span == block.span => self.block = Some(block),
+                    Some(_) => return,
+                }
+                rustc_hir::intravisit::walk_block(self, block);
+            }
+        }
+
+        let mut finder = BlockFinder { tcx: self.cx.tcx, block: None };
+        rustc_hir::intravisit::Visitor::visit_expr(&mut finder, self.body.value);
+        Some(Block { cx: self.cx, inner: finder.block? })
+    }
+}
+/// A `use` item importing a single item, like `use serenity::utils::parse_username as parse;`.
+/// Each item in `use a::{b, c};` is a separate `use` item.
+#[derive(Debug, Clone, Copy)]
+pub struct Use<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Item<'hir>,
+    path: &'hir rustc_hir::UsePath<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Use<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> Use<'hir> {
+    /// Returns None if the item is not a `use` item, or if it's a glob import
+    pub fn new(
+        cx: &rustc_lint::LateContext<'hir>,
+        inner: &'hir rustc_hir::Item<'hir>,
+    ) -> Option<Self> {
+        let rustc_hir::ItemKind::Use(path, rustc_hir::UseKind::Single) = inner.kind else {
+            return None;
+        };
+        Some(Self { cx: Context::new(cx), inner, path })
+    }
+
+    /// The definition the imported path resolves to. Paths which resolve to items in multiple
+    /// namespaces resolve to the first one.
+    pub fn path(&self) -> Option<Path<'hir>> {
+        let def_id = self.path.res.iter().find_map(|res| res.opt_def_id())?;
+        Some(Path { cx: self.cx, inner: self.cx.tcx.def_path(def_id) })
+    }
+
+    /// The last segment of the imported path, like `parse_username` in
+    /// `use serenity::utils::parse_username as parse;`
+    pub fn path_name(&self) -> Option<String> {
+        Some(self.path.segments.last()?.ident.to_string())
+    }
+
+    pub fn path_name_span(&self) -> Option<rustc_span::Span> {
+        Some(self.path.segments.last()?.ident.span)
+    }
+
+    /// Span of the imported path, like `a::b` in `use a::b as c;`
+    pub fn path_span(&self) -> rustc_span::Span {
+        self.path.span
+    }
+
+    /// Whether the import is part of a nested import like `use a::{b, c};`
+    pub fn is_nested(&self) -> bool {
+        // The path of each import in a nested import starts with the shared prefix, which is
+        // outside of the path's span
+        let span = self.path.span;
+        self.path
+            .segments
+            .iter()
+            .any(|segment| !segment.ident.span.is_dummy() && !span.contains(segment.ident.span))
+    }
+
+    /// The name the import is bound to, like `c` in `use a::b as c;`
+    pub fn name(&self) -> String {
+        self.inner.ident.to_string()
+    }
+
+    /// The other imports in the same module or block
+    pub fn sibling_uses(&self) -> Vec<Use<'hir>> {
+        let hir = self.cx.tcx.hir();
+        let parent = hir.get_parent_item(self.inner.hir_id());
+        hir.items()
+            .map(|id| hir.item(id))
+            .filter(|item| {
+                item.hir_id() != self.inner.hir_id() && hir.get_parent_item(item.hir_id()) == parent
+            })
+            .filter_map(|inner| {
+                let rustc_hir::ItemKind::Use(path, rustc_hir::UseKind::Single) = inner.kind else {
+                    return None;
+                };
+                Some(Self { cx: self.cx, inner, path })
+            })
+            .collect()
+    }
+}
+/// A `static` item, like the `DELETE_COMMAND_OPTIONS` generated by serenity's `#[command]`
+#[derive(Debug, Clone, Copy)]
+pub struct Static<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Item<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Static<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> Static<'hir> {
+    /// Returns None if the item is not a `static`
+    pub fn new(
+        cx: &rustc_lint::LateContext<'hir>,
+        inner: &'hir rustc_hir::Item<'hir>,
+    ) -> Option<Self> {
+        let rustc_hir::ItemKind::Static(..) = inner.kind else { return None };
+        Some(Self { cx: Context::new(cx), inner })
+    }
+
+    pub fn type_(&self) -> Type<'hir> {
+        let inner = self.cx.tcx.type_of(self.inner.owner_id).subst_identity();
+        Type { cx: self.cx, inner }
+    }
+
+    /// The attributes following the attribute macro which generated this item, like
+    /// `#[required_permissions(ADMINISTRATOR)]` after `#[command]`. The macro consumes them, so
+    /// they're only found in the source code.
+    pub fn macro_helper_attributes(&self) -> Vec<MacroAttribute<'hir>> {
+        let expn = self.inner.span.ctxt().outer_expn_data();
+        let rustc_span::hygiene::ExpnKind::Macro(rustc_span::hygiene::MacroKind::Attr, _) =
+            expn.kind
+        else {
+            return Vec::new();
+        };
+        let macro_span = expn.call_site;
+        let Ok(source) = self.cx.tcx.sess.source_map().span_to_next_source(macro_span) else {
+            return Vec::new();
+        };
+        let span_at = |lo: usize, hi: usize| {
+            let pos = |offset: usize| macro_span.hi() + rustc_span::BytePos(offset as u32);
+            macro_span.with_hi(pos(hi)).with_lo(pos(lo))
+        };
+
+        let mut attributes = Vec::new();
+        // The call site may or may not include the closing bracket of the attribute macro
+        let mut rest = source.strip_prefix(']').unwrap_or(&source);
+        loop {
+            rest = rest.trim_start();
+            if rest.starts_with("//") {
+                rest = rest.split_once('\n').map_or("", |(_, next_lines)| next_lines);
+                continue;
+            }
+            if !rest.starts_with("#[") {
+                break;
+            }
+            let Some(len) = attribute_len(rest) else { break };
+            let offset = source.len() - rest.len();
+            attributes.push(MacroAttribute { cx: self.cx, span: span_at(offset, offset + len) });
+            rest = &rest[len..];
+        }
+        attributes
+    }
+}
+
+/// Length of the attribute at the start of `source`, like `#[description("[a]")]`
+fn attribute_len(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut chars = source.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '[' if !in_string => depth += 1,
+            ']' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// An attribute which was consumed by an attribute macro, like
+/// `#[required_permissions(ADMINISTRATOR)]` for `#[command]`
+#[derive(Debug, Clone, Copy)]
+pub struct MacroAttribute<'hir> {
+    cx: Context<'hir>,
+    span: rustc_span::Span,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for MacroAttribute<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.span }
+}
+impl<'hir> MacroAttribute<'hir> {
+    /// Like `required_permissions` in `#[required_permissions(ADMINISTRATOR)]`
+    pub fn name(&self) -> String {
+        let source = self.source_code();
+        let name = source.trim_start_matches("#[").trim_start();
+        name.chars().take_while(|&c| c.is_alphanumeric() || c == '_' || c == ':').collect()
+    }
+
+    /// Identifiers in the arguments and their spans, like `ADMINISTRATOR` in
+    /// `#[required_permissions(ADMINISTRATOR)]`. Identifiers in string literals are skipped.
+    pub fn argument_idents(&self) -> Vec<(String, rustc_span::Span)> {
+        let source = self.source_code();
+        let Some(args_start) = source.find('(') else { return Vec::new() };
+
+        let mut idents = Vec::new();
+        let mut in_string = false;
+        let mut ident_start = None;
+        let mut chars = source.char_indices().skip_while(|&(i, _)| i <= args_start);
+        while let Some((i, c)) = chars.next() {
+            let is_ident_char = c.is_alphanumeric() || c == '_';
+            if let Some(start) = ident_start.filter(|_| !is_ident_char) {
+                let pos = |offset: usize| self.span.lo() + rustc_span::BytePos(offset as u32);
+                idents.push((
+                    source[start..i].to_owned(),
+                    self.span.with_lo(pos(start)).with_hi(pos(i)),
+                ));
+                ident_start = None;
+            }
+            match c {
+                '\\' if in_string => {
+                    chars.next();
+                }
+                '"' => in_string = !in_string,
+                _ if is_ident_char && !in_string && ident_start.is_none() => ident_start = Some(i),
+                _ => {}
+            }
+        }
+        idents
+    }
 }
 #[derive(Debug)]
 pub struct Closure<'hir> {
@@ -128,9 +775,10 @@ impl<'hir> Closure<'hir> {
         args.next().filter(|_| args.next().is_none())
     }
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct MethodCall<'hir> {
     cx: Context<'hir>,
+    hir_id: rustc_hir::HirId,
     receiver: &'hir rustc_hir::Expr<'hir>,
     method: &'hir rustc_hir::PathSegment<'hir>,
     args: &'hir [rustc_hir::Expr<'hir>],
@@ -150,16 +798,143 @@ impl<'hir> MethodCall<'hir> {
         self.method.ident.to_string()
     }
 
+    pub fn method_name_span(&self) -> rustc_span::Span {
+        self.method.ident.span
+    }
+
+    /// The type whose impl block defines the called method
+    pub fn self_type(&self) -> Option<Type<'hir>> {
+        let def_id = self.cx.typeck_results().type_dependent_def_id(self.hir_id)?;
+        impl_self_type(self.cx, def_id)
+    }
+
     pub fn args(&self) -> impl Iterator<Item = Expr<'hir>> + 'hir {
         let cx = self.cx;
         self.args.iter().map(move |arg| Expr { cx, inner: arg })
     }
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+pub struct Call<'hir> {
+    cx: Context<'hir>,
+    func: &'hir rustc_hir::Expr<'hir>,
+    args: &'hir [rustc_hir::Expr<'hir>],
+    span: rustc_span::Span,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Call<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.span }
+}
+impl<'hir> Call<'hir> {
+    pub fn func(&self) -> Expr<'hir> {
+        Expr { cx: self.cx, inner: self.func }
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = Expr<'hir>> + 'hir {
+        let cx = self.cx;
+        self.args.iter().map(move |arg| Expr { cx, inner: arg })
+    }
+}
+/// A field access like `user_id.0`
+#[derive(Debug, Clone, Copy)]
+pub struct Field<'hir> {
+    cx: Context<'hir>,
+    base: &'hir rustc_hir::Expr<'hir>,
+    name: rustc_span::symbol::Ident,
+    span: rustc_span::Span,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Field<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.span }
+}
+impl<'hir> Field<'hir> {
+    pub fn base(&self) -> Expr<'hir> {
+        Expr { cx: self.cx, inner: self.base }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    pub fn name_span(&self) -> rustc_span::Span {
+        self.name.span
+    }
+}
+#[derive(Debug, Clone, Copy)]
+pub struct Stmt<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Stmt<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Stmt<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> Stmt<'hir> {
+    /// The expression in an expression statement with semicolon, like `e.title("x");`
+    pub fn semi(&self) -> Option<Expr<'hir>> {
+        let rustc_hir::StmtKind::Semi(inner) = self.inner.kind else { return None };
+        Some(Expr { cx: self.cx, inner })
+    }
+
+    /// The expression in an expression statement with or without semicolon, like `if x { .. }`
+    pub fn expr(&self) -> Option<Expr<'hir>> {
+        match self.inner.kind {
+            rustc_hir::StmtKind::Expr(inner) | rustc_hir::StmtKind::Semi(inner) => {
+                Some(Expr { cx: self.cx, inner })
+            }
+            _ => None,
+        }
+    }
+}
+#[derive(Debug, Clone, Copy)]
+pub struct Block<'hir> {
+    cx: Context<'hir>,
+    inner: &'hir rustc_hir::Block<'hir>,
+}
+#[rustfmt::skip]
+impl<'hir> Node<'hir> for Block<'hir> {
+    fn cx(&self) -> Context<'hir> { self.cx }
+    fn span(&self) -> rustc_span::Span { self.inner.span }
+}
+impl<'hir> Block<'hir> {
+    pub fn stmts(&self) -> impl Iterator<Item = Stmt<'hir>> + 'hir {
+        let cx = self.cx;
+        self.inner.stmts.iter().map(move |inner| Stmt { cx, inner })
+    }
+
+    pub fn expr(&self) -> Option<Expr<'hir>> {
+        Some(Expr { cx: self.cx, inner: self.inner.expr? })
+    }
+
+    /// Statements of this block and of all blocks nested within, excluding closure bodies
+    pub fn stmts_recursive(&self) -> Vec<Stmt<'hir>> {
+        struct StmtCollector<'hir> {
+            stmts: Vec<&'hir rustc_hir::Stmt<'hir>>,
+        }
+        impl<'hir> rustc_hir::intravisit::Visitor<'hir> for StmtCollector<'hir> {
+            fn visit_stmt(&mut self, stmt: &'hir rustc_hir::Stmt<'hir>) {
+                self.stmts.push(stmt);
+                rustc_hir::intravisit::walk_stmt(self, stmt);
+            }
+        }
+
+        let mut collector = StmtCollector { stmts: Vec::new() };
+        rustc_hir::intravisit::Visitor::visit_block(&mut collector, self.inner);
+        collector.stmts.into_iter().map(|inner| Stmt { cx: self.cx, inner }).collect()
+    }
+}
+#[derive(Debug, Clone, Copy)]
 pub struct Expr<'hir> {
     cx: Context<'hir>,
     inner: &'hir rustc_hir::Expr<'hir>,
 }
+impl PartialEq for Expr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.hir_id == other.inner.hir_id
+    }
+}
 #[rustfmt::skip]
 impl<'hir> Node<'hir> for Expr<'hir> {
     fn cx(&self) -> Context<'hir> { self.cx }
@@ -167,8 +942,7 @@ impl<'hir> Node<'hir> for Expr<'hir> {
 }
 impl<'hir> Expr<'hir> {
     pub fn new(cx: &rustc_lint::LateContext<'hir>, inner: &'hir rustc_hir::Expr<'hir>) -> Self {
-        let cx = Context { tcx: cx.tcx, typeck_results: cx.typeck_results() };
-        Self { cx, inner }
+        Self { cx: Context::new(cx), inner }
     }
 
     pub fn closure(&self) -> Option<Closure<'hir>> {
@@ -180,13 +954,458 @@ impl<'hir> Expr<'hir> {
 
     pub fn method_call(&self) -> Option<MethodCall<'hir>> {
         match self.inner.kind {
-            rustc_hir::ExprKind::MethodCall(method, receiver, args, span) => {
-                Some(MethodCall { cx: self.cx, method, receiver, args, span })
+            rustc_hir::ExprKind::MethodCall(method, receiver, args, span) => Some(MethodCall {
+                cx: self.cx,
+                hir_id: self.inner.hir_id,
+                method,
+                receiver,
+                args,
+                span,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn call(&self) -> Option<Call<'hir>> {
+        match self.inner.kind {
+            rustc_hir::ExprKind::Call(func, args) => {
+                Some(Call { cx: self.cx, func, args, span: self.inner.span })
             }
             _ => None,
         }
     }
 
+    pub fn field(&self) -> Option<Field<'hir>> {
+        let rustc_hir::ExprKind::Field(base, name) = self.inner.kind else { return None };
+        Some(Field { cx: self.cx, base, name, span: self.inner.span })
+    }
+
+    /// The dereferenced expression, like `x` in `*x`
+    pub fn deref(&self) -> Option<Expr<'hir>> {
+        let rustc_hir::ExprKind::Unary(rustc_hir::UnOp::Deref, inner) = self.inner.kind else {
+            return None;
+        };
+        Some(Expr { cx: self.cx, inner })
+    }
+
+    pub fn block(&self) -> Option<Block<'hir>> {
+        let rustc_hir::ExprKind::Block(inner, _) = self.inner.kind else { return None };
+        Some(Block { cx: self.cx, inner })
+    }
+
+    pub fn type_(&self) -> Type<'hir> {
+        Type { cx: self.cx, inner: self.cx.typeck_results().expr_ty(self.inner) }
+    }
+
+    /// The type after implicit dereferencing and borrowing, like `&Mutex<T>` for `x` in
+    /// `x.lock()` if `x` is an `Arc<Mutex<T>>`
+    pub fn adjusted_type(&self) -> Type<'hir> {
+        Type { cx: self.cx, inner: self.cx.typeck_results().expr_ty_adjusted(self.inner) }
+    }
+
+    /// The definition a path expression like `InteractionResponseType::Modal` resolves to
+    pub fn path(&self) -> Option<Path<'hir>> {
+        let rustc_hir::ExprKind::Path(qpath) = &self.inner.kind else { return None };
+        qpath_def_path(self.cx, qpath, self.inner.hir_id)
+    }
+
+    /// For a path to an associated function like `Command::set_global_application_commands`, the
+    /// type whose impl block defines it
+    pub fn impl_self_type(&self) -> Option<Type<'hir>> {
+        let rustc_hir::ExprKind::Path(qpath) = &self.inner.kind else { return None };
+        let def_id = self.cx.typeck_results().qpath_res(qpath, self.inner.hir_id).opt_def_id()?;
+        impl_self_type(self.cx, def_id)
+    }
+
+    /// Span of the last segment of a path expression, like `b` in `a::b`
+    pub fn path_name_span(&self) -> Option<rustc_span::Span> {
+        let rustc_hir::ExprKind::Path(qpath) = &self.inner.kind else { return None };
+        qpath_name_span(qpath)
+    }
+
+    /// Span of a path expression as written, like `interaction::Interaction::Ping`, and its
+    /// number of segments. Type-relative paths like `Self::new` aren't included.
+    pub fn written_path_span(&self) -> Option<(rustc_span::Span, usize)> {
+        let rustc_hir::ExprKind::Path(qpath) = &self.inner.kind else { return None };
+        qpath_written_span(qpath)
+    }
+
+    /// The definition the path of a struct expression like `AttachmentType::Bytes { .. }` resolves
+    /// to
+    pub fn struct_path(&self) -> Option<Path<'hir>> {
+        let rustc_hir::ExprKind::Struct(qpath, ..) = &self.inner.kind else { return None };
+        qpath_def_path(self.cx, qpath, self.inner.hir_id)
+    }
+
+    /// Fields of a struct expression and their values, like `data` and `filename` in
+    /// `AttachmentType::Bytes { data, filename }`. Returns None if the struct expression has a base,
+    /// like `..Default::default()`.
+    pub fn struct_fields(&self) -> Option<Vec<(String, Expr<'hir>)>> {
+        let rustc_hir::ExprKind::Struct(_, fields, None) = self.inner.kind else { return None };
+        let fields = fields
+            .iter()
+            .map(|field| (field.ident.to_string(), Expr { cx: self.cx, inner: field.expr }));
+        Some(fields.collect())
+    }
+
+    /// The last segment of a path expression as written, like `b` in `a::b`. This is the alias
+    /// for items imported with `use .. as ..`.
+    pub fn path_name(&self) -> Option<String> {
+        let rustc_hir::ExprKind::Path(qpath) = &self.inner.kind else { return None };
+        let segment = match qpath {
+            rustc_hir::QPath::Resolved(_, path) => path.segments.last()?,
+            rustc_hir::QPath::TypeRelative(_, segment) => segment,
+            rustc_hir::QPath::LangItem(..) => return None,
+        };
+        Some(segment.ident.to_string())
+    }
+
+    /// Whether this expression is a path to the given parameter, like `e` in `|e| e`
+    pub fn refers_to(&self, param: &Param<'hir>) -> bool {
+        let rustc_hir::ExprKind::Path(rustc_hir::QPath::Resolved(None, path)) = self.inner.kind else { return false };
+        path.res == rustc_hir::def::Res::Local(param.inner.pat.hir_id)
+    }
+
+    /// Whether this expression is a path to the given binding, like `guild` in
+    /// `if let Some(guild) = .. { guild }`
+    pub fn refers_to_binding(&self, binding: &Pat<'hir>) -> bool {
+        let rustc_hir::ExprKind::Path(rustc_hir::QPath::Resolved(None, path)) = self.inner.kind else { return false };
+        path.res == rustc_hir::def::Res::Local(binding.inner.hir_id)
+    }
+
+    pub fn parent(&self) -> Option<Expr<'hir>> {
+        match self.cx.tcx.hir().find_parent(self.inner.hir_id)? {
+            rustc_hir::Node::Expr(inner) => Some(Expr { cx: self.cx, inner }),
+            _ => None,
+        }
+    }
+
+    /// Patterns which this expression's value is bound to, like `guild` in
+    /// `let guild = expr;`, `Some(guild)` in `if let Some(guild) = expr` or the patterns of all
+    /// arms in `match expr { .. }`
+    pub fn bound_pats(&self) -> Vec<Pat<'hir>> {
+        let cx = self.cx;
+        let is_self = |expr: &rustc_hir::Expr<'_>| expr.hir_id == self.inner.hir_id;
+        match self.cx.tcx.hir().find_parent(self.inner.hir_id) {
+            Some(rustc_hir::Node::Local(local)) if local.init.map_or(false, is_self) => {
+                vec![Pat { cx, inner: local.pat }]
+            }
+            Some(rustc_hir::Node::Expr(parent)) => match parent.kind {
+                rustc_hir::ExprKind::Let(let_) if is_self(let_.init) => {
+                    vec![Pat { cx, inner: let_.pat }]
+                }
+                rustc_hir::ExprKind::Match(scrutinee, arms, rustc_hir::MatchSource::Normal)
+                    if is_self(scrutinee) =>
+                {
+                    arms.iter().map(|arm| Pat { cx, inner: arm.pat }).collect()
+                }
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Where the patterns of [`Self::bound_pats`] are in scope: the rest of the block for
+    /// `let guild = expr;`, and the whole `if let` or `match` expression otherwise. If the `if let`
+    /// or `match` expression is bound itself, like in
+    /// `let guild = match expr { Some(guild) => guild, None => return };`, its scope is included.
+    pub fn binding_scope(&self) -> Option<rustc_span::Span> {
+        let hir = self.cx.tcx.hir();
+        match hir.find_parent(self.inner.hir_id)? {
+            rustc_hir::Node::Local(_) => {
+                let block =
+                    hir.parent_iter(self.inner.hir_id).find_map(|(_, parent)| match parent {
+                        rustc_hir::Node::Block(block) => Some(block),
+                        _ => None,
+                    })?;
+                Some(block.span.with_lo(self.inner.span.hi()))
+            }
+            rustc_hir::Node::Expr(parent) => match parent.kind {
+                rustc_hir::ExprKind::Let(_) => {
+                    let if_ =
+                        hir.parent_iter(parent.hir_id).find_map(|(_, parent)| match parent {
+                            rustc_hir::Node::Expr(
+                                inner @ rustc_hir::Expr {
+                                    kind: rustc_hir::ExprKind::If(..), ..
+                                },
+                            ) => Some(Expr { cx: self.cx, inner }),
+                            _ => None,
+                        })?;
+                    Some(if_.binding_scope().map_or(if_.span(), |outer| if_.span().to(outer)))
+                }
+                rustc_hir::ExprKind::Match(..) => {
+                    let match_ = Expr { cx: self.cx, inner: parent };
+                    Some(match_.binding_scope().map_or(parent.span, |outer| parent.span.to(outer)))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The arms of a `match` expression as written by the user, i.e. not desugared
+    pub fn match_arms(&self) -> Option<Vec<Arm<'hir>>> {
+        let rustc_hir::ExprKind::Match(_, arms, rustc_hir::MatchSource::Normal) = self.inner.kind
+        else {
+            return None;
+        };
+        Some(arms.iter().map(|inner| Arm { cx: self.cx, inner }).collect())
+    }
+
+    /// The indexed expression, like `a` in `a[i]`
+    pub fn index_base(&self) -> Option<Expr<'hir>> {
+        let rustc_hir::ExprKind::Index(inner, _) = self.inner.kind else { return None };
+        Some(Expr { cx: self.cx, inner })
+    }
+
+    /// For an argument of a call to a generic function, the traits which its parameter's type
+    /// has to implement, like `CacheHttp` for `cache_http` in
+    /// `fn reply(&self, cache_http: impl CacheHttp, content: &str)`
+    pub fn param_trait_bounds(&self) -> Vec<Path<'hir>> {
+        let tcx = self.cx.tcx;
+        let is_self = |arg: &rustc_hir::Expr<'_>| arg.hir_id == self.inner.hir_id;
+        let Some(parent) = self.parent() else { return Vec::new() };
+        let (def_id, index) = match parent.inner.kind {
+            rustc_hir::ExprKind::MethodCall(_, receiver, args, _) => {
+                let typeck_results = self.cx.typeck_results();
+                let Some(def_id) = typeck_results.type_dependent_def_id(parent.inner.hir_id) else {
+                    return Vec::new();
+                };
+                // The receiver is the first input of the method's signature
+                let index = match args.iter().position(is_self) {
+                    Some(index) => index + 1,
+                    None if is_self(receiver) => 0,
+                    None => return Vec::new(),
+                };
+                (def_id, index)
+            }
+            rustc_hir::ExprKind::Call(func, args) => {
+                let rustc_hir::ExprKind::Path(qpath) = &func.kind else { return Vec::new() };
+                let res = self.cx.typeck_results().qpath_res(qpath, func.hir_id);
+                let (Some(def_id), Some(index)) = (res.opt_def_id(), args.iter().position(is_self))
+                else {
+                    return Vec::new();
+                };
+                (def_id, index)
+            }
+            _ => return Vec::new(),
+        };
+        if !matches!(
+            tcx.def_kind(def_id),
+            rustc_hir::def::DefKind::Fn | rustc_hir::def::DefKind::AssocFn
+        ) {
+            return Vec::new();
+        }
+
+        let Some(&param) = tcx.fn_sig(def_id).subst_identity().skip_binder().inputs().get(index)
+        else {
+            return Vec::new();
+        };
+        if !matches!(param.kind(), rustc_middle::ty::TyKind::Param(_)) {
+            return Vec::new();
+        }
+        tcx.predicates_of(def_id)
+            .predicates
+            .iter()
+            .filter_map(|(predicate, _)| predicate.to_opt_poly_trait_pred())
+            .filter(|predicate| predicate.skip_binder().self_ty() == param)
+            .map(|predicate| Path { cx: self.cx, inner: tcx.def_path(predicate.def_id()) })
+            .collect()
+    }
+
+    /// The borrowed expression, like `x` in `&x`
+    pub fn borrowed(&self) -> Option<Expr<'hir>> {
+        let rustc_hir::ExprKind::AddrOf(_, _, inner) = self.inner.kind else { return None };
+        Some(Expr { cx: self.cx, inner })
+    }
+
+    /// Whether this is an `x.await` expression
+    pub fn is_await(&self) -> bool {
+        matches!(
+            self.inner.kind,
+            rustc_hir::ExprKind::Match(_, _, rustc_hir::MatchSource::AwaitDesugar)
+        )
+    }
+
+    /// All expressions in the innermost function, closure or async block containing this
+    /// expression, excluding nested closures and async blocks
+    pub fn enclosing_body_exprs(&self) -> Vec<Expr<'hir>> {
+        struct ExprCollector<'hir> {
+            exprs: Vec<&'hir rustc_hir::Expr<'hir>>,
+        }
+        impl<'hir> rustc_hir::intravisit::Visitor<'hir> for ExprCollector<'hir> {
+            fn visit_expr(&mut self, expr: &'hir rustc_hir::Expr<'hir>) {
+                self.exprs.push(expr);
+                rustc_hir::intravisit::walk_expr(self, expr);
+            }
+        }
+
+        let hir = self.cx.tcx.hir();
+        let body = hir.body(hir.body_owned_by(hir.enclosing_body_owner(self.inner.hir_id)));
+        let mut collector = ExprCollector { exprs: Vec::new() };
+        rustc_hir::intravisit::Visitor::visit_body(&mut collector, body);
+        collector.exprs.into_iter().map(|inner| Expr { cx: self.cx, inner }).collect()
+    }
+
+    /// Whether a path in the function containing this expression, including its closures and
+    /// async blocks, ends with `name`, like `framework` in `framework.group(..)`
+    pub fn body_refers_to_name(&self, name: &str) -> bool {
+        struct PathFinder<'a, 'hir> {
+            tcx: rustc_middle::ty::TyCtxt<'hir>,
+            name: &'a str,
+            found: bool,
+        }
+        impl<'hir> rustc_hir::intravisit::Visitor<'hir> for PathFinder<'_, 'hir> {
+            type NestedFilter = rustc_middle::hir::nested_filter::OnlyBodies;
+            type Map = rustc_middle::hir::map::Map<'hir>;
+
+            fn nested_visit_map(&mut self) -> Self::Map {
+                self.tcx.hir()
+            }
+
+            fn visit_path(&mut self, path: &rustc_hir::Path<'hir>, _id: rustc_hir::HirId) {
+                if path.segments.last().map_or(false, |segment| segment.ident.as_str() == self.name)
+                {
+                    self.found = true;
+                }
+                rustc_hir::intravisit::walk_path(self, path);
+            }
+        }
+
+        let hir = self.cx.tcx.hir();
+        let owner = hir.enclosing_body_owner(self.inner.hir_id);
+        let Some(owner) = self.cx.tcx.typeck_root_def_id(owner.to_def_id()).as_local() else {
+            return false;
+        };
+        let body = hir.body(hir.body_owned_by(owner));
+        let mut finder = PathFinder { tcx: self.cx.tcx, name, found: false };
+        rustc_hir::intravisit::Visitor::visit_body(&mut finder, body);
+        finder.found
+    }
+
+    /// The statement or trailing expression of the innermost block containing this expression,
+    /// like `let x = f(expr);` for `expr`. Returns its span and the indentation of its first line.
+    pub fn enclosing_block_item(&self) -> Option<(rustc_span::Span, String)> {
+        let hir = self.cx.tcx.hir();
+        let mut item = self.inner.hir_id;
+        for (parent_id, parent) in hir.parent_iter(self.inner.hir_id) {
+            if let rustc_hir::Node::Block(_) = parent {
+                let span = rustc_span::hygiene::walk_chain(
+                    hir.span(item),
+                    rustc_span::SyntaxContext::root(),
+                );
+                let preceding = self.cx.tcx.sess.source_map().span_to_prev_source(span).ok()?;
+                let line = preceding.rsplit('\n').next().unwrap_or_default();
+                let indent = &line[..line.len() - line.trim_start().len()];
+                return Some((span, indent.to_owned()));
+            }
+            item = parent_id;
+        }
+        None
+    }
+
+    /// This expression and all expressions nested within, outermost first
+    pub fn subexprs(&self) -> Vec<Expr<'hir>> {
+        struct ExprCollector<'hir> {
+            exprs: Vec<&'hir rustc_hir::Expr<'hir>>,
+        }
+        impl<'hir> rustc_hir::intravisit::Visitor<'hir> for ExprCollector<'hir> {
+            fn visit_expr(&mut self, expr: &'hir rustc_hir::Expr<'hir>) {
+                self.exprs.push(expr);
+                rustc_hir::intravisit::walk_expr(self, expr);
+            }
+        }
+
+        let mut collector = ExprCollector { exprs: Vec::new() };
+        rustc_hir::intravisit::Visitor::visit_expr(&mut collector, self.inner);
+        collector.exprs.into_iter().map(|inner| Expr { cx: self.cx, inner }).collect()
+    }
+
+    /// The type in a type-relative path expression, like `Activity` in `Activity::playing`
+    pub fn path_qself(&self) -> Option<HirType<'hir>> {
+        let rustc_hir::ExprKind::Path(rustc_hir::QPath::TypeRelative(inner, _)) = self.inner.kind
+        else {
+            return None;
+        };
+        Some(HirType { cx: self.cx, inner })
+    }
+
+    /// If this expression is awaited, the span of `.await`
+    pub fn await_span(&self) -> Option<rustc_span::Span> {
+        // `x.await` is desugared into `match IntoFuture::into_future(x) { .. }`
+        let mut parents = self.cx.tcx.hir().parent_iter(self.inner.hir_id);
+        let (_, rustc_hir::Node::Expr(into_future)) = parents.next()? else { return None };
+        let (_, rustc_hir::Node::Expr(awaited)) = parents.next()? else { return None };
+        match awaited.kind {
+            rustc_hir::ExprKind::Match(scrutinee, _, rustc_hir::MatchSource::AwaitDesugar)
+                if scrutinee.hir_id == into_future.hir_id =>
+            {
+                Some(awaited.span.with_lo(self.inner.span.hi()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Closures and async blocks containing this expression, innermost first
+    pub fn enclosing_closures(&self) -> Vec<Expr<'hir>> {
+        self.cx
+            .tcx
+            .hir()
+            .parent_iter(self.inner.hir_id)
+            .filter_map(|(_, node)| match node {
+                rustc_hir::Node::Expr(
+                    inner @ rustc_hir::Expr { kind: rustc_hir::ExprKind::Closure(_), .. },
+                ) => Some(Expr { cx: self.cx, inner }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the innermost function, closure or async block containing this expression is
+    /// async, so that `.await` can be used here
+    pub fn in_async_body(&self) -> bool {
+        let owner = self.cx.tcx.hir().enclosing_body_owner(self.inner.hir_id);
+        matches!(self.cx.tcx.generator_kind(owner), Some(rustc_hir::GeneratorKind::Async(_)))
+    }
+
+    /// Return type of the innermost function, closure or async block containing this expression.
+    /// That's where the `?` operator would return to.
+    pub fn enclosing_return_type(&self) -> Option<Type<'hir>> {
+        let tcx = self.cx.tcx;
+        let owner = tcx.hir().enclosing_body_owner(self.inner.hir_id);
+        let inner = if tcx.is_closure(owner.to_def_id()) {
+            let closure_id = tcx.hir().local_def_id_to_hir_id(owner);
+            match self.cx.typeck_results().node_type(closure_id).kind() {
+                rustc_middle::ty::TyKind::Closure(_, substs) => {
+                    substs.as_closure().sig().output().skip_binder()
+                }
+                rustc_middle::ty::TyKind::Generator(_, substs, _) => {
+                    substs.as_generator().return_ty()
+                }
+                _ => return None,
+            }
+        } else {
+            tcx.fn_sig(owner).subst_identity().output().skip_binder()
+        };
+        Some(Type { cx: self.cx, inner })
+    }
+
+    pub fn is_integer_literal(&self) -> bool {
+        let rustc_hir::ExprKind::Lit(lit) = self.inner.kind else { return false };
+        matches!(lit.node, rustc_ast::LitKind::Int(..))
+    }
+
+    /// The condition, the `then` block and the `else` branch of an `if` expression
+    pub fn if_else(&self) -> Option<(Expr<'hir>, Expr<'hir>, Option<Expr<'hir>>)> {
+        let rustc_hir::ExprKind::If(condition, then, else_) = self.inner.kind else { return None };
+        let cx = self.cx;
+        Some((
+            Expr { cx, inner: condition },
+            Expr { cx, inner: then },
+            else_.map(|inner| Expr { cx, inner }),
+        ))
+    }
+
     pub fn single_expr_block(&self) -> Option<Expr<'hir>> {
         let rustc_hir::ExprKind::Block(block, _) = self.inner.kind else { return None };
         if !block.stmts.is_empty() {
@@ -14,23 +14,94 @@ static LINT: rustc_lint::Lint = rustc_lint::Lint {
     crate_level_only: true,
 };
 
-fn emit_replacement(cx: &rustc_lint::LateContext<'_>, span: rustc_span::Span, replacement: &str) {
-    cx.lint(
-        &LINT,
-        "closure-style builders have been replaced in the next version of serenity",
-        |b| {
-            b.span_note(span, "replace this...").span_suggestion(
-                span,
-                "...with",
-                replacement,
-                rustc_errors::Applicability::MachineApplicable,
-            )
-        },
-    );
+/// Code which may be affected by serenity 0.12, but doesn't have to be changed
+static CACHE_GUARD_LINT: rustc_lint::Lint = rustc_lint::Lint {
+    name: "serenity_0_12_cache_guards",
+    default_level: rustc_lint::Level::Warn,
+    desc: "finds cache getters which return a guard in serenity 0.12",
+    edition_lint_opts: None,
+    report_in_external_macro: false,
+    future_incompatible: None,
+    is_plugin: true,
+    feature_gate: None,
+    crate_level_only: true,
+};
+
+// Lint messages, which describe what changed in serenity 0.12
+const BUILDERS: &str = "closure-style builders have been replaced in the next version of serenity";
+const RENAMED_FN: &str = "this function has been renamed in the next version of serenity";
+const RENAMED_ITEM: &str = "this item has been renamed in the next version of serenity";
+const IDS: &str = "IDs wrap a `NonZeroU64` in the next version of serenity";
+const ACTIVITIES: &str = "activities are set differently in the next version of serenity";
+const CACHE_AND_HTTP: &str = "`CacheAndHttp` has been removed in the next version of serenity";
+const SHARD_MANAGER: &str =
+    "`ShardManager` isn't wrapped in a `Mutex` anymore in the next version of serenity";
+const CACHE_GETTERS: &str = "cache getters return a guard in the next version of serenity";
+const GUILD_CHANNELS: &str =
+    "`Guild::channels` contains `GuildChannel`s in the next version of serenity";
+const CATEGORIES: &str = "`Channel::Category` has been removed in the next version of serenity";
+const EVENT_HANDLER: &str =
+    "this event handler method has different parameters in the next version of serenity";
+const EDIT_CALLS: &str = "this method takes `&mut self` in the next version of serenity";
+const ATTACHMENTS: &str =
+    "`AttachmentType` has been replaced by `CreateAttachment` in the next version of serenity";
+const COLLECTORS: &str = "collectors have changed in the next version of serenity";
+const CREATE_WEBHOOK: &str = "this method takes a `CreateWebhook` in the next version of serenity";
+const FAKE_EMBEDS: &str = "`Embed::fake` has been removed in the next version of serenity";
+const FRAMEWORK_CONFIGURE: &str =
+    "`StandardFramework::configure` takes a `Configuration` in the next version of serenity";
+
+/// Suggests replacing parts of the code at `span`, with an optional note explaining the change
+fn emit_migration(
+    cx: &rustc_lint::LateContext<'_>,
+    message: &str,
+    span: rustc_span::Span,
+    replacements: Vec<(rustc_span::Span, String)>,
+    applicability: rustc_errors::Applicability,
+    note: Option<&str>,
+) {
+    cx.lint(&LINT, message, |b| {
+        b.span_note(span, "replace this...").multipart_suggestion(
+            "...with",
+            replacements,
+            applicability,
+        );
+        if let Some(note) = note {
+            b.note(note);
+        }
+        b
+    });
+}
+
+fn emit_renamed_path(cx: &rustc_lint::LateContext<'_>, span: rustc_span::Span, new_name: String) {
+    let applicability = rustc_errors::Applicability::MachineApplicable;
+    emit_migration(cx, RENAMED_ITEM, span, vec![(span, new_name)], applicability, None);
+}
+
+fn emit_cache_getter_warning(
+    cx: &rustc_lint::LateContext<'_>,
+    unsupported: crate::migrate::Unsupported,
+) {
+    cx.struct_span_lint(&CACHE_GUARD_LINT, unsupported.span, CACHE_GETTERS, |b| {
+        b.note(unsupported.message).help("if the value is needed across an `.await`, clone it")
+    });
+}
+
+/// Explains why the code can't be migrated automatically
+fn emit_unsupported(
+    cx: &rustc_lint::LateContext<'_>,
+    message: &str,
+    unsupported: crate::migrate::Unsupported,
+) {
+    cx.struct_span_lint(&LINT, unsupported.span, message, |b| {
+        b.note(unsupported.message).help("this must be migrated manually")
+    });
 }
 
 struct Visitor<'hir, 'anon> {
     cx: &'anon rustc_lint::LateContext<'hir>,
+    /// Code which already has a suggested replacement, so it mustn't be visited again
+    migrated_spans: Vec<rustc_span::Span>,
 }
 impl<'hir> rustc_hir::intravisit::Visitor<'hir> for Visitor<'hir, '_> {
     type NestedFilter = rustc_middle::hir::nested_filter::OnlyBodies;
@@ -41,11 +112,239 @@ impl<'hir> rustc_hir::intravisit::Visitor<'hir> for Visitor<'hir, '_> {
     }
 
     fn visit_expr(&mut self, expr: &'hir rustc_hir::Expr<'hir>) {
-        if let Some(replacement) = crate::migrate::migrate(crate::nodes::Expr::new(self.cx, expr)) {
-            emit_replacement(self.cx, expr.span, &replacement);
-        } else {
-            rustc_hir::intravisit::walk_expr(self, expr);
+        if self.is_migrated(expr.span) {
+            return;
+        }
+
+        let node = crate::nodes::Expr::new(self.cx, expr);
+        if let Some(migration) = crate::migrate::migrate_cache_and_http_field(node) {
+            match migration {
+                Ok(replacement) => {
+                    self.migrated_spans.push(expr.span);
+                    self.emit(CACHE_AND_HTTP, expr.span, vec![(expr.span, replacement)], None);
+                }
+                Err(unsupported) => emit_unsupported(self.cx, CACHE_AND_HTTP, unsupported),
+            }
+            return;
+        } else if let Some(span) = crate::migrate::migrate_shard_manager_lock(node) {
+            self.migrated_spans.push(span);
+            self.emit(SHARD_MANAGER, span, vec![(span, String::new())], None);
+        } else if let Some(migration) = crate::migrate::migrate_cache_getter(node) {
+            match migration {
+                Ok((insertion_point, clone)) => {
+                    let note = "this is held across an `.await`, so it must be cloned";
+                    self.emit(CACHE_GETTERS, expr.span, vec![(insertion_point, clone)], Some(note));
+                }
+                Err(unsupported) => emit_cache_getter_warning(self.cx, unsupported),
+            }
+        } else if let Some(migrations) = crate::migrate::migrate_category_arms(node) {
+            for migration in migrations {
+                match migration {
+                    Ok((span, replacement)) => {
+                        self.migrated_spans.push(span);
+                        self.emit(CATEGORIES, span, vec![(span, replacement)], None);
+                    }
+                    Err(unsupported) => emit_unsupported(self.cx, CATEGORIES, unsupported),
+                }
+            }
+        } else if let Some(renamed) = crate::migrate::migrate_expr_path(node) {
+            self.rename_path(renamed);
+        } else if let Some(renamed) = crate::migrate::migrate_permission(node) {
+            self.rename_path(renamed);
+        } else if let Some(replacements) = crate::migrate::migrate_utils_fn(node) {
+            self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+            let note = "the function returns an ID type like `UserId` instead of a `u64` now";
+            self.emit(RENAMED_FN, expr.span, replacements, Some(note));
+        } else if let Some(migration) = crate::migrate::migrate_attachment(node) {
+            match migration {
+                Ok(migration) => {
+                    let replacements = vec![(expr.span, migration.replacement)];
+                    let fallback_reason = migration.fallback_reason;
+                    self.emit_with_fallback(ATTACHMENTS, expr.span, replacements, fallback_reason);
+                }
+                Err(unsupported) => emit_unsupported(self.cx, ATTACHMENTS, unsupported),
+            }
+            return;
+        } else if let Some(replacements) = crate::migrate::migrate_id(node) {
+            self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+            self.emit(IDS, expr.span, replacements, None);
+        } else if let Some(crate::migrate::ActivityMigration { replacements, fallback_reason }) =
+            crate::migrate::migrate_activity(node)
+        {
+            self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+            self.emit_with_fallback(ACTIVITIES, expr.span, replacements, fallback_reason);
+        } else if let Some(migration) = crate::migrate::migrate_edit_call(node) {
+            match migration {
+                Ok(replacement) => {
+                    let note = "the method on the ID returns the edited value like before";
+                    self.emit(EDIT_CALLS, expr.span, vec![(expr.span, replacement)], Some(note));
+                }
+                Err(unsupported) => emit_unsupported(self.cx, EDIT_CALLS, unsupported),
+            }
+            return;
+        } else if let Some(migration) = crate::migrate::migrate_collector(node) {
+            match migration {
+                Ok(replacements) => {
+                    self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+                    let note = "collected items aren't wrapped in an `Arc` anymore";
+                    self.emit(COLLECTORS, expr.span, replacements, Some(note));
+                }
+                Err(unsupported) => emit_unsupported(self.cx, COLLECTORS, unsupported),
+            }
+        } else if let Some(migration) = crate::migrate::migrate_call_site(node) {
+            match migration {
+                Ok(replacements) => {
+                    self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+                    self.emit(RENAMED_FN, expr.span, replacements, None);
+                }
+                Err(unsupported) => return emit_unsupported(self.cx, BUILDERS, unsupported),
+            }
+        } else if let Some(migration) = crate::migrate::migrate_create_webhook(node) {
+            match migration {
+                Ok(crate::migrate::CreateWebhookMigration { replacements, fallback_reason }) => {
+                    self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+                    self.emit_with_fallback(
+                        CREATE_WEBHOOK,
+                        expr.span,
+                        replacements,
+                        fallback_reason,
+                    );
+                }
+                Err(unsupported) => return emit_unsupported(self.cx, CREATE_WEBHOOK, unsupported),
+            }
+        } else if let Some(migration) = crate::migrate::migrate_fake_embed(node) {
+            match migration {
+                Ok(replacement) => {
+                    let note = "webhooks take `CreateEmbed`s instead of JSON values";
+                    self.emit(FAKE_EMBEDS, expr.span, vec![(expr.span, replacement)], Some(note));
+                }
+                Err(unsupported) => emit_unsupported(self.cx, FAKE_EMBEDS, unsupported),
+            }
+            return;
+        } else if let Some(migration) = crate::migrate::migrate_framework_configure(node) {
+            match migration {
+                Ok(replacements) => {
+                    self.migrated_spans.extend(replacements.iter().map(|(span, _)| *span));
+                    let note = "`configure` takes `&self` and can't be chained anymore";
+                    self.emit(FRAMEWORK_CONFIGURE, expr.span, replacements, Some(note));
+                }
+                Err(unsupported) => emit_unsupported(self.cx, FRAMEWORK_CONFIGURE, unsupported),
+            }
+            return;
+        } else if let Some(migration) = crate::migrate::migrate_builder(node) {
+            match migration {
+                Ok(crate::migrate::BuilderMigration { replacement, fallback_reason }) => {
+                    let replacements = vec![(expr.span, replacement)];
+                    self.emit_with_fallback(BUILDERS, expr.span, replacements, fallback_reason);
+                }
+                Err(unsupported) => emit_unsupported(self.cx, BUILDERS, unsupported),
+            }
+            return;
+        }
+        rustc_hir::intravisit::walk_expr(self, expr);
+    }
+
+    // Array lengths and inline consts are separate bodies without type information from the
+    // enclosing function, and there's nothing to migrate in them
+    fn visit_anon_const(&mut self, _: &'hir rustc_hir::AnonConst) {}
+
+    fn visit_pat(&mut self, pat: &'hir rustc_hir::Pat<'hir>) {
+        if self.is_migrated(pat.span) {
+            return;
         }
+
+        let node = crate::nodes::Pat::new(self.cx, pat);
+        if let Some(renamed) = crate::migrate::migrate_pat_path(node) {
+            self.rename_path(renamed);
+        } else if let Some(migration) = crate::migrate::migrate_guild_channel_pat(node) {
+            match migration {
+                Ok(replacements) => {
+                    self.migrated_spans.push(pat.span);
+                    self.emit(GUILD_CHANNELS, pat.span, replacements, None);
+                }
+                Err(unsupported) => emit_unsupported(self.cx, GUILD_CHANNELS, unsupported),
+            }
+            return;
+        }
+        rustc_hir::intravisit::walk_pat(self, pat);
+    }
+
+    fn visit_ty(&mut self, type_: &'hir rustc_hir::Ty<'hir>) {
+        if self.is_migrated(type_.span) {
+            return;
+        }
+
+        let node = crate::nodes::HirType::new(self.cx, type_);
+        if let Some(renamed) = crate::migrate::migrate_type_path(node) {
+            self.rename_path(renamed);
+        } else if let Some((span, replacement)) = crate::migrate::migrate_cache_and_http_type(node)
+        {
+            if !self.is_migrated(span) {
+                self.migrated_spans.push(span);
+                let note = "if the cache is needed as well, store an `Arc<Cache>` separately";
+                emit_migration(
+                    self.cx,
+                    CACHE_AND_HTTP,
+                    span,
+                    vec![(span, replacement)],
+                    rustc_errors::Applicability::MaybeIncorrect,
+                    Some(note),
+                );
+            }
+        } else if let Some((span, replacement)) = crate::migrate::migrate_shard_manager_type(node) {
+            if !self.is_migrated(span) {
+                self.migrated_spans.push(span);
+                self.emit(SHARD_MANAGER, span, vec![(span, replacement)], None);
+            }
+            return;
+        }
+        // The return type of an async fn is desugared into an `impl Future<Output = ..>` item
+        if let rustc_hir::TyKind::OpaqueDef(item_id, ..) = type_.kind {
+            self.visit_item(self.cx.tcx.hir().item(item_id));
+        }
+        rustc_hir::intravisit::walk_ty(self, type_);
+    }
+}
+impl Visitor<'_, '_> {
+    fn is_migrated(&self, span: rustc_span::Span) -> bool {
+        self.migrated_spans.iter().any(|migrated| migrated.contains(span))
+    }
+
+    fn rename_path(&mut self, (span, new_name): (rustc_span::Span, String)) {
+        // Desugared code may contain the same path more than once
+        if self.is_migrated(span) {
+            return;
+        }
+        self.migrated_spans.push(span);
+        emit_renamed_path(self.cx, span, new_name);
+    }
+
+    /// Suggests a migration which is known to be correct
+    fn emit(
+        &self,
+        message: &str,
+        span: rustc_span::Span,
+        replacements: Vec<(rustc_span::Span, String)>,
+        note: Option<&str>,
+    ) {
+        let applicability = rustc_errors::Applicability::MachineApplicable;
+        emit_migration(self.cx, message, span, replacements, applicability, note);
+    }
+
+    /// Suggests a migration which may fall back to panicking, like with `.expect(..)` where an
+    /// error can't be propagated with `?`. The reason for the fallback is shown as a note.
+    fn emit_with_fallback(
+        &self,
+        message: &str,
+        span: rustc_span::Span,
+        replacements: Vec<(rustc_span::Span, String)>,
+        fallback_reason: Option<&str>,
+    ) {
+        let applicability = match fallback_reason {
+            Some(_) => rustc_errors::Applicability::MaybeIncorrect,
+            None => rustc_errors::Applicability::MachineApplicable,
+        };
+        emit_migration(self.cx, message, span, replacements, applicability, fallback_reason);
     }
 }
 
@@ -61,7 +360,7 @@ impl<'tcx> rustc_lint::LateLintPass<'tcx> for Lint {
         &mut self,
         cx: &rustc_lint::LateContext<'tcx>,
         kind: rustc_hir::intravisit::FnKind<'tcx>,
-        _: &'tcx rustc_hir::FnDecl<'tcx>,
+        decl: &'tcx rustc_hir::FnDecl<'tcx>,
         body: &'tcx rustc_hir::Body<'tcx>,
         span: rustc_span::Span,
         _: rustc_span::def_id::LocalDefId,
@@ -70,9 +369,78 @@ impl<'tcx> rustc_lint::LateLintPass<'tcx> for Lint {
         if let rustc_hir::intravisit::FnKind::Closure = kind {
             return;
         }
-        let mut visitor = Visitor { cx };
+        let mut visitor = Visitor { cx, migrated_spans: Vec::new() };
+        // Parameter and return types are part of the signature, not the body
+        visitor.visit_fn_decl(decl);
         visitor.visit_body(body);
     }
+
+    fn check_item(
+        &mut self,
+        cx: &rustc_lint::LateContext<'tcx>,
+        item: &'tcx rustc_hir::Item<'tcx>,
+    ) {
+        if let Some(use_) = crate::nodes::Use::new(cx, item) {
+            if let Some((span, new_name)) = crate::migrate::migrate_utils_use(use_) {
+                emit_renamed_path(cx, span, new_name);
+            } else if let Some(migration) = crate::migrate::migrate_use_path(use_) {
+                match migration {
+                    Ok(crate::migrate::UseMigration { span, replacement, caveat }) => {
+                        let applicability = match caveat {
+                            Some(_) => rustc_errors::Applicability::MaybeIncorrect,
+                            None => rustc_errors::Applicability::MachineApplicable,
+                        };
+                        let replacements = vec![(span, replacement)];
+                        let caveat = caveat.as_deref();
+                        emit_migration(cx, RENAMED_ITEM, span, replacements, applicability, caveat);
+                    }
+                    Err(unsupported) => emit_unsupported(cx, RENAMED_ITEM, unsupported),
+                }
+            }
+        }
+
+        // Like `DELETE_COMMAND_OPTIONS`, generated by `#[command]` from attributes like
+        // `#[required_permissions(..)]`
+        let Some(static_) = crate::nodes::Static::new(cx, item) else { return };
+        for (span, new_name) in crate::migrate::migrate_required_permissions(static_) {
+            emit_renamed_path(cx, span, new_name);
+        }
+    }
+
+    fn check_field_def(
+        &mut self,
+        cx: &rustc_lint::LateContext<'tcx>,
+        field: &'tcx rustc_hir::FieldDef<'tcx>,
+    ) {
+        use rustc_hir::intravisit::Visitor as _;
+        let mut visitor = Visitor { cx, migrated_spans: Vec::new() };
+        visitor.visit_ty(field.ty);
+    }
+
+    fn check_impl_item(
+        &mut self,
+        cx: &rustc_lint::LateContext<'tcx>,
+        impl_item: &'tcx rustc_hir::ImplItem<'tcx>,
+    ) {
+        use rustc_hir::intravisit::Visitor as _;
+        // Like `type Value = Arc<Mutex<ShardManager>>;` in `TypeMapKey` impls
+        if let rustc_hir::ImplItemKind::Type(type_) = impl_item.kind {
+            let mut visitor = Visitor { cx, migrated_spans: Vec::new() };
+            visitor.visit_ty(type_);
+        }
+
+        let Some(method) = crate::nodes::ImplFn::new(cx, impl_item) else { return };
+        if let Some(crate::migrate::EventHandlerMigration { replacements, caveat }) =
+            crate::migrate::migrate_event_handler_fn(method)
+        {
+            let applicability = match caveat {
+                Some(_) => rustc_errors::Applicability::MaybeIncorrect,
+                None => rustc_errors::Applicability::MachineApplicable,
+            };
+            let span = impl_item.ident.span;
+            emit_migration(cx, EVENT_HANDLER, span, replacements, applicability, caveat);
+        }
+    }
 }
 
 struct RustcCallbacks;
@@ -80,7 +448,7 @@ impl rustc_driver::Callbacks for RustcCallbacks {
     fn config(&mut self, config: &mut rustc_interface::Config) {
         // Called on every crate
         config.register_lints = Some(Box::new(|session, lints| {
-            lints.late_passes.push(Box::new(|_cx| Box::new(Lint::default())));
+            lints.late_passes.push(Box::new(|_cx| Box::<Lint>::default()));
         }));
     }
 }